@@ -3,29 +3,43 @@ use std::sync::mpsc;
 use ahash::HashMap;
 use glam::*;
 use strum::IntoDiscriminant;
-use wasm_bindgen::{JsCast as _, UnwrapThrowExt as _};
+use vello_svg::vello;
+use wasm_bindgen::{JsCast as _, JsValue, UnwrapThrowExt as _};
+use web_sys::js_sys;
 
 use crate::{
-    controller::{BackgroundController, PanelController, SkillsController},
+    blur::BlurPipeline,
+    config::{BackgroundConfig, ContourConfig, GridConfig, LiquidRenderMode, OutlineConfig},
+    controller::{PanelController, SkillsController},
     delta_time::DeltaTime,
     ext::{CanvasExt as _, SurfaceConfigurationExt as _, Vec4Ext, WindowExt},
     frame::FrameMetadata,
     gpu::Gpu,
-    grid::{GridMetadata, GridState},
+    grid::{
+        GridMetadata, GridSpring, GridState,
+        pipeline::{GridInputs, GridProcessor, GridRenderer},
+    },
+    impulse::{Impulse, Impulses},
+    layer::BackgroundLayers,
+    light::{Light, LiquidLight},
+    liquid_material::{LiquidMaterial, LiquidMaterialPreset, LiquidMaterialTween},
     mar_sq::{
+        contour_line::{ContourChannel, ContourLevels, ContourLineStyle, ContourLines},
+        line_segment::{LineSegments, OutlineStyle},
         pipeline::{
-            MarchingSquaresLiquidQuadRenderer, MarchingSquaresProcessor,
-            MarchingSquaresShapeRenderer,
+            LiquidShading, MarchingSquaresContourLineRenderer, MarchingSquaresLiquidFusedRenderer,
+            MarchingSquaresLiquidQuadRenderer, MarchingSquaresOutlineRenderer,
+            MarchingSquaresProcessor, MarchingSquaresShapeRenderer,
         },
         quad::Quads,
+        triangle::Triangles,
     },
     meta_field::MetaField,
     meta_shape::{MetaBall, MetaShapes},
     mouse::Mouse,
-    pipeline::{
-        BackgroundImageRenderer, BackgroundSvgRenderer, GaussianBlurPipeline, MetaFieldGrad,
-        MetaFieldProcessor, MetaFieldRenderer,
-    },
+    pipeline::{MetaFieldGrad, MetaFieldProcessor, MetaFieldRenderer, create_vello_renderer},
+    post_effect::{PostEffectChain, PostEffectKind, PostEffectParams},
+    svg_export,
     texture_blitter::TextureBlitter,
     theme::{Theme, ThemePropertyName},
 };
@@ -36,6 +50,400 @@ use crate::{
 pub enum BackgroundEvent {
     Resize,
     MouseMove(IVec2),
+    Ripple(IVec2),
+    BlurRadius(f32),
+    LiquidMaterial(LiquidMaterialPreset),
+    LightPosition(Option<Vec2>),
+    LightColor(Vec3),
+    GridSpring {
+        stiffness: f32,
+        damping: f32,
+    },
+    PostEffectEnabled(PostEffectKind, bool),
+    PostEffectParams(PostEffectParams),
+    /// Resolves the function with the liquid SVG on the next update.
+    ExportLiquidSvg(js_sys::Function),
+}
+
+enum LiquidRenderer {
+    Quad {
+        quads: Quads,
+        processor: MarchingSquaresProcessor<Quads>,
+        shape_renderer: MarchingSquaresShapeRenderer<Quads>,
+        renderer: MarchingSquaresLiquidQuadRenderer,
+    },
+    Fused(MarchingSquaresLiquidFusedRenderer),
+    Mesh {
+        triangles: Triangles,
+        processor: MarchingSquaresProcessor<Triangles>,
+        renderer: MarchingSquaresShapeRenderer<Triangles>,
+    },
+    None,
+}
+
+impl LiquidRenderer {
+    fn new(
+        mode: LiquidRenderMode,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+        background_view: &wgpu::TextureView,
+        shading: LiquidShading,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        log::debug!("Liquid render mode: {mode}");
+
+        match mode {
+            LiquidRenderMode::Quad => {
+                let quads = Quads::new(device, meta_field);
+
+                let processor = MarchingSquaresProcessor::new(device, meta_field, &quads);
+
+                let shape_renderer = MarchingSquaresShapeRenderer::new(
+                    device,
+                    frame_metadata,
+                    &quads,
+                    texture_format,
+                );
+
+                let renderer = MarchingSquaresLiquidQuadRenderer::new(
+                    device,
+                    frame_metadata,
+                    &quads,
+                    meta_field,
+                    background_view,
+                    shading,
+                    texture_format,
+                );
+
+                Self::Quad {
+                    quads,
+                    processor,
+                    shape_renderer,
+                    renderer,
+                }
+            }
+            LiquidRenderMode::Fused => Self::Fused(MarchingSquaresLiquidFusedRenderer::new(
+                device,
+                frame_metadata,
+                meta_field,
+                background_view,
+                shading,
+                texture_format,
+            )),
+            LiquidRenderMode::Mesh => {
+                let triangles = Triangles::new(device, meta_field);
+
+                let processor = MarchingSquaresProcessor::new(device, meta_field, &triangles);
+
+                let renderer = MarchingSquaresShapeRenderer::new(
+                    device,
+                    frame_metadata,
+                    &triangles,
+                    texture_format,
+                );
+
+                Self::Mesh {
+                    triangles,
+                    processor,
+                    renderer,
+                }
+            }
+            LiquidRenderMode::None => Self::None,
+        }
+    }
+
+    fn recreate_bind_group(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+        background_view: &wgpu::TextureView,
+    ) {
+        match self {
+            Self::Quad {
+                quads,
+                processor,
+                shape_renderer,
+                renderer,
+            } => {
+                processor.recreate_bind_group(device, meta_field, quads);
+                shape_renderer.recreate_bind_group(device, frame_metadata, quads);
+                renderer.recreate_bind_group(
+                    device,
+                    frame_metadata,
+                    quads,
+                    meta_field,
+                    background_view,
+                );
+            }
+            Self::Fused(renderer) => {
+                renderer.recreate_bind_group(device, frame_metadata, meta_field, background_view);
+            }
+            Self::Mesh {
+                triangles,
+                processor,
+                renderer,
+            } => {
+                processor.recreate_bind_group(device, meta_field, triangles);
+                renderer.recreate_bind_group(device, frame_metadata, triangles);
+            }
+            Self::None => {}
+        }
+    }
+
+    fn set_light(&self, queue: &wgpu::Queue, light: &LiquidLight) {
+        match self {
+            Self::Quad { renderer, .. } => renderer.set_light(queue, light),
+            Self::Fused(renderer) => renderer.set_light(queue, light),
+            Self::Mesh { .. } | Self::None => {}
+        }
+    }
+
+    fn set_material(&self, queue: &wgpu::Queue, material: &LiquidMaterial) {
+        match self {
+            Self::Quad { renderer, .. } => renderer.set_material(queue, material),
+            Self::Fused(renderer) => renderer.set_material(queue, material),
+            Self::Mesh { .. } | Self::None => {}
+        }
+    }
+
+    fn process(
+        &self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        meta_field_resolution: UVec2,
+    ) {
+        match self {
+            Self::Quad { processor, .. } => {
+                processor.process(queue, encoder, meta_field_resolution);
+            }
+            Self::Mesh { processor, .. } => {
+                processor.process(queue, encoder, meta_field_resolution);
+            }
+            Self::Fused(..) | Self::None => {}
+        }
+    }
+
+    fn render(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        match self {
+            Self::Quad {
+                processor,
+                renderer,
+                ..
+            } => renderer.render(encoder, view, processor),
+            Self::Fused(renderer) => renderer.render(encoder, view),
+            Self::Mesh {
+                processor,
+                renderer,
+                ..
+            } => renderer.render(encoder, view, processor),
+            Self::None => {}
+        }
+    }
+}
+
+struct Outline {
+    line_segments: LineSegments,
+    processor: MarchingSquaresProcessor<LineSegments>,
+    renderer: MarchingSquaresOutlineRenderer,
+    config: OutlineConfig,
+    theme: Theme,
+}
+
+impl Outline {
+    fn new(
+        config: &OutlineConfig,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let line_segments = LineSegments::new(device, meta_field);
+
+        let processor = MarchingSquaresProcessor::new(device, meta_field, &line_segments);
+
+        let renderer = MarchingSquaresOutlineRenderer::new(
+            device,
+            frame_metadata,
+            &line_segments,
+            OutlineStyle::from_theme(config.color, config.width),
+            texture_format,
+        );
+
+        Self {
+            line_segments,
+            processor,
+            renderer,
+            config: *config,
+            theme: Theme::current(),
+        }
+    }
+
+    /// Recolours the outline from the current theme if it changed.
+    fn update_theme(&mut self, queue: &wgpu::Queue) {
+        let theme = Theme::current();
+        if self.theme == theme {
+            return;
+        }
+
+        self.theme = theme;
+        self.renderer.update_style(
+            queue,
+            OutlineStyle::from_theme(self.config.color, self.config.width),
+        );
+    }
+
+    fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+    ) {
+        self.line_segments = LineSegments::new(device, meta_field);
+
+        self.processor
+            .recreate_bind_group(device, meta_field, &self.line_segments);
+
+        self.renderer
+            .recreate_bind_group(device, frame_metadata, &self.line_segments);
+    }
+}
+
+struct Contours {
+    contour_lines: ContourLines,
+    processor: MarchingSquaresProcessor<ContourLines>,
+    renderer: MarchingSquaresContourLineRenderer,
+    width: f32,
+    theme: Theme,
+}
+
+impl Contours {
+    fn new(
+        config: &ContourConfig,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let (min, max) = match config.channel {
+            ContourChannel::Magnitude => (0.2, 1.0),
+            // Panels rise by at most half of the hover offset.
+            ContourChannel::Elevation => (0.5, 4.0),
+        };
+        let levels = ContourLevels::evenly_spaced(config.channel, min, max, config.count);
+
+        let contour_lines = ContourLines::new(device, meta_field, levels);
+
+        let processor = MarchingSquaresProcessor::new(device, meta_field, &contour_lines);
+
+        let renderer = MarchingSquaresContourLineRenderer::new(
+            device,
+            frame_metadata,
+            &contour_lines,
+            ContourLineStyle::from_theme(&levels, config.width),
+            texture_format,
+        );
+
+        Self {
+            contour_lines,
+            processor,
+            renderer,
+            width: config.width,
+            theme: Theme::current(),
+        }
+    }
+
+    /// Restyles the lines from the current theme if it changed.
+    fn update_theme(&mut self, queue: &wgpu::Queue) {
+        let theme = Theme::current();
+        if self.theme == theme {
+            return;
+        }
+
+        self.theme = theme;
+        self.renderer.update_style(
+            queue,
+            ContourLineStyle::from_theme(self.contour_lines.levels(), self.width),
+        );
+    }
+
+    fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+    ) {
+        self.contour_lines.resize(device, meta_field);
+
+        self.processor
+            .recreate_bind_group(device, meta_field, &self.contour_lines);
+
+        self.renderer
+            .recreate_bind_group(device, frame_metadata, &self.contour_lines);
+    }
+}
+
+struct Grid {
+    metadata: GridMetadata,
+    state: GridState,
+    spring: GridSpring,
+    processor: GridProcessor,
+    renderer: GridRenderer,
+}
+
+impl Grid {
+    fn new(
+        config: &GridConfig,
+        device: &wgpu::Device,
+        inputs: GridInputs,
+        mouse: &Mouse,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let metadata = GridMetadata::new(device, inputs.frame_metadata, config.cell_size);
+        let state = GridState::new(device, &metadata);
+        let spring = GridSpring::new(device, config.stiffness, config.damping);
+
+        let processor =
+            GridProcessor::new(device, inputs, &metadata, &state, &spring, mouse.position());
+
+        let renderer = GridRenderer::new(
+            device,
+            inputs.frame_metadata,
+            &metadata,
+            &state,
+            texture_format,
+        );
+
+        Self {
+            metadata,
+            state,
+            spring,
+            processor,
+            renderer,
+        }
+    }
+
+    fn resize(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, inputs: GridInputs) {
+        self.metadata.update(queue, inputs.frame_metadata);
+
+        self.state.resize(device, queue, &self.metadata);
+
+        self.processor.recreate_bind_group(
+            device,
+            inputs,
+            &self.metadata,
+            &self.state,
+            &self.spring,
+        );
+
+        self.renderer.recreate_bind_group(
+            device,
+            inputs.frame_metadata,
+            &self.metadata,
+            &self.state,
+        );
+    }
 }
 
 pub struct Background {
@@ -44,46 +452,45 @@ pub struct Background {
     frame_timer: web_time::Instant,
     fps_display_counter: u32,
     mouse: Mouse,
+    light: Light,
 
     // Controller
     skills_controller: SkillsController,
-    background_controller: BackgroundController,
     panel_controller: PanelController,
 
     // Pipelines
-    skills_renderer: BackgroundSvgRenderer,
-    background_renderer: BackgroundImageRenderer,
-    blur: GaussianBlurPipeline,
-    // grid_processor: GridProcessor,
-    // grid_renderer: GridRenderer,
+    vello_renderer: vello::Renderer,
+    layers: BackgroundLayers,
+    blur: BlurPipeline,
     meta_field_processor: MetaFieldProcessor,
     meta_field_renderer: MetaFieldRenderer<MetaFieldGrad>,
-    marching_squares_processor: MarchingSquaresProcessor<Quads>,
-    marching_squares_shape_renderer: MarchingSquaresShapeRenderer<Quads>,
-    marching_squares_liquid_quad_renderer: MarchingSquaresLiquidQuadRenderer,
+    liquid_renderer: LiquidRenderer,
+    liquid_material: LiquidMaterialTween,
+    outline: Option<Outline>,
+    contours: Option<Contours>,
+    grid: Option<Grid>,
+    post_effects: PostEffectChain,
     surface_blitter: TextureBlitter,
 
     // Data
     frame_metadata: FrameMetadata,
     background: wgpu::Texture,
     delta_time: DeltaTime,
-    grid_state: GridState,
-    grid_metadata: GridMetadata,
     meta_shapes: MetaShapes,
     meta_field: MetaField,
-    // line_segments: LineSegments,
-    quads: Quads,
+    impulses: Impulses,
+    svg_export_requests: Vec<js_sys::Function>,
 }
 
 impl Background {
     pub async fn new(
         gpu: Gpu,
         canvas: web_sys::HtmlCanvasElement,
+        config: BackgroundConfig,
         background_events: mpsc::Receiver<BackgroundEvent>,
     ) -> Self {
-        let skills_controller = SkillsController::new();
+        let mut skills_controller = SkillsController::new();
 
-        let background_controller = BackgroundController::new();
         let mut panel_controller = PanelController::new();
 
         let frame_metadata = FrameMetadata::new(&gpu.device, canvas.size(), IVec2::ZERO);
@@ -106,46 +513,50 @@ impl Background {
         });
 
         let delta_time = DeltaTime::new(&gpu.device);
-        let grid_metadata = GridMetadata::new(&gpu.device, &frame_metadata);
-        let grid_state = GridState::new(&gpu.device, &grid_metadata);
 
         let frame_timer = web_time::Instant::now();
 
         let mouse = Mouse::new(frame_metadata.resolution().as_vec2() / 2.0);
 
+        let light = Light::new(&config.light, mouse.position());
+
         let meta_shapes = MetaShapes::new_with_controller(&gpu.device, &mut panel_controller);
 
+        let impulses = Impulses::new(&gpu.device);
+
         const CELL_SIZE: u32 = 4;
         let meta_field = MetaField::new(&gpu.device, &frame_metadata, CELL_SIZE);
 
-        // let line_segments = LineSegments::new(&gpu.device, &meta_field);
-        let quads = Quads::new(&gpu.device, &meta_field);
-
-        let skills_renderer =
-            BackgroundSvgRenderer::new_skills(&gpu.device, gpu.config.format).await;
+        let vello_renderer = create_vello_renderer(&gpu.device);
 
-        let background_renderer =
-            BackgroundImageRenderer::new_background(&gpu.device, &gpu.queue, gpu.config.format)
-                .await;
-
-        let blur = GaussianBlurPipeline::new(&gpu.device, &frame_metadata, gpu.config.format);
+        let layers = BackgroundLayers::load(&gpu.device, &gpu.queue, gpu.config.format).await;
+        match layers.svg_elements(&skills_controller.element.id()) {
+            Some(elements) => skills_controller.set_icons(elements),
+            None => log::warn!("No SVG layer over #skills, the skill icons cannot be hovered"),
+        }
 
-        // let grid_processor = GridProcessor::new(
-        //     &gpu.device,
-        //     &frame_metadata,
-        //     &grid_metadata,
-        //     &delta_time,
-        //     &grid_state,
-        //     mouse.position(),
-        // );
+        let blur = BlurPipeline::new(
+            &config.blur,
+            &gpu.device,
+            &frame_metadata,
+            &background.create_view(&wgpu::TextureViewDescriptor::default()),
+            gpu.config.format,
+        );
 
-        // let grid_renderer = GridRenderer::new(
-        //     &gpu.device,
-        //     &frame_metadata,
-        //     &grid_metadata,
-        //     &grid_state,
-        //     gpu.config.format,
-        // );
+        let grid = config.grid.map(|grid| {
+            Grid::new(
+                &grid,
+                &gpu.device,
+                GridInputs {
+                    frame_metadata: &frame_metadata,
+                    delta_time: &delta_time,
+                    meta_shapes: &meta_shapes,
+                    impulses: &impulses,
+                },
+                &mouse,
+                gpu.config.format,
+            )
+        });
 
         let meta_field_processor =
             MetaFieldProcessor::new(&gpu.device, &frame_metadata, &meta_shapes, &meta_field);
@@ -153,29 +564,54 @@ impl Background {
         let meta_field_renderer =
             MetaFieldRenderer::new(&gpu.device, &meta_field, gpu.config.format);
 
-        let marching_squares_processor =
-            MarchingSquaresProcessor::new(&gpu.device, &meta_field, &quads);
+        let liquid_material = LiquidMaterialTween::new(config.liquid_material.material());
 
-        let marching_squares_shape_renderer = MarchingSquaresShapeRenderer::new(
+        let liquid_renderer = LiquidRenderer::new(
+            config.liquid_render_mode,
             &gpu.device,
             &frame_metadata,
-            &quads,
+            &meta_field,
+            blur.output_view(),
+            LiquidShading {
+                background_color: Theme::current()
+                    .properties()
+                    .get(&ThemePropertyName::Background)
+                    .expect_throw("background color")
+                    .vec4()
+                    .expect("background color vector")
+                    .xyz(),
+                material: &liquid_material.current(),
+                light: &light.uniform(&frame_metadata),
+                dispersion: config.dispersion,
+            },
             gpu.config.format,
         );
 
-        let marching_squares_liquid_quad_renderer = MarchingSquaresLiquidQuadRenderer::new(
+        let contours = config.contours.map(|contours| {
+            Contours::new(
+                &contours,
+                &gpu.device,
+                &frame_metadata,
+                &meta_field,
+                gpu.config.format,
+            )
+        });
+
+        let outline = config.outline.map(|outline| {
+            Outline::new(
+                &outline,
+                &gpu.device,
+                &frame_metadata,
+                &meta_field,
+                gpu.config.format,
+            )
+        });
+
+        let post_effects = PostEffectChain::new(
+            &config.post_effects,
             &gpu.device,
             &frame_metadata,
-            &quads,
             &meta_field,
-            blur.output_view(),
-            Theme::current()
-                .properties()
-                .get(&ThemePropertyName::Background)
-                .expect_throw("background color")
-                .vec4()
-                .expect("background color vector")
-                .xyz(),
             gpu.config.format,
         );
 
@@ -187,31 +623,29 @@ impl Background {
             frame_timer,
             fps_display_counter: 0,
             mouse,
+            light,
 
-            skills_renderer,
-            background_renderer,
+            vello_renderer,
+            layers,
             blur,
-            // grid_processor,
-            // grid_renderer,
             meta_field_processor,
             meta_field_renderer,
-            marching_squares_processor,
-            marching_squares_shape_renderer,
-            marching_squares_liquid_quad_renderer,
+            liquid_renderer,
+            liquid_material,
+            outline,
+            contours,
+            grid,
+            post_effects,
             surface_blitter,
 
             frame_metadata,
             background,
             delta_time,
-            grid_state,
-            grid_metadata,
             meta_shapes,
             meta_field,
-            // line_segments,
-            quads,
-
+            impulses,
+            svg_export_requests: Vec::new(),
             panel_controller,
-            background_controller,
             skills_controller,
         }
     }
@@ -238,16 +672,53 @@ impl Background {
     }
 
     fn handle_event(&mut self) {
-        let event_map = self
-            .background_events
-            .try_iter()
-            .map(|event| (event.discriminant(), event))
-            .collect::<HashMap<_, _>>();
+        let mut event_map = HashMap::default();
+        for event in self.background_events.try_iter() {
+            match event {
+                // Every export request is answered, so they are not deduplicated.
+                BackgroundEvent::ExportLiquidSvg(resolve) => self.svg_export_requests.push(resolve),
+                // Each effect is toggled and tuned separately, so these apply in order.
+                BackgroundEvent::PostEffectEnabled(kind, enabled) => {
+                    if !self.post_effects.contains(kind) {
+                        log::warn!("Post effect {kind} is not in the chain, add it with `post=`");
+                    } else if self.post_effects.is_enabled(kind) != enabled {
+                        log::debug!("Post effect {kind}: {enabled}");
+                        self.post_effects.set_enabled(kind, enabled);
+                    }
+                }
+                BackgroundEvent::PostEffectParams(params) => {
+                    self.post_effects.set_params(&self.gpu.queue, params)
+                }
+                event => {
+                    event_map.insert(event.discriminant(), event);
+                }
+            }
+        }
 
         for event in event_map.into_values() {
             match event {
                 BackgroundEvent::Resize => self.handle_resize(),
                 BackgroundEvent::MouseMove(pos) => self.handle_mouse_move(pos),
+                BackgroundEvent::Ripple(pos) => self.handle_ripple(pos),
+                BackgroundEvent::BlurRadius(radius) => {
+                    self.blur.set_radius(&self.gpu.queue, radius)
+                }
+                BackgroundEvent::LiquidMaterial(preset) => {
+                    log::debug!("Liquid material: {preset}");
+                    self.liquid_material.start(preset.material());
+                }
+                BackgroundEvent::LightPosition(position) => self.light.set_pinned(position),
+                BackgroundEvent::LightColor(color) => self.light.set_color(color),
+                BackgroundEvent::GridSpring { stiffness, damping } => {
+                    if let Some(grid) = &self.grid {
+                        grid.spring.update(&self.gpu.queue, stiffness, damping);
+                    }
+                }
+                BackgroundEvent::ExportLiquidSvg(_)
+                | BackgroundEvent::PostEffectEnabled(..)
+                | BackgroundEvent::PostEffectParams(_) => {
+                    unreachable!("handled in order")
+                }
             }
         }
     }
@@ -293,11 +764,18 @@ impl Background {
             view_formats: &[],
         });
 
-        self.grid_metadata
-            .update(&self.gpu.queue, &self.frame_metadata);
-
-        self.grid_state
-            .resize(&self.gpu.device, &self.grid_metadata);
+        if let Some(grid) = &mut self.grid {
+            grid.resize(
+                &self.gpu.device,
+                &self.gpu.queue,
+                GridInputs {
+                    frame_metadata: &self.frame_metadata,
+                    delta_time: &self.delta_time,
+                    meta_shapes: &self.meta_shapes,
+                    impulses: &self.impulses,
+                },
+            );
+        }
 
         self.panel_controller
             .resize(&mut self.meta_shapes, window.scroll_pos());
@@ -305,22 +783,13 @@ impl Background {
         self.meta_field
             .resize(&self.gpu.device, &self.frame_metadata);
 
-        self.blur.resize(&self.gpu.device, &self.frame_metadata);
-
-        // self.grid_processor.recreate_bind_group(
-        //     &self.gpu.device,
-        //     &self.frame_metadata,
-        //     &self.grid_metadata,
-        //     &self.delta_time,
-        //     &self.grid_state,
-        // );
-
-        // self.grid_renderer.recreate_bind_group(
-        //     &self.gpu.device,
-        //     &self.frame_metadata,
-        //     &self.grid_metadata,
-        //     &self.grid_state,
-        // );
+        self.blur.resize(
+            &self.gpu.device,
+            &self.frame_metadata,
+            &self
+                .background
+                .create_view(&wgpu::TextureViewDescriptor::default()),
+        );
 
         self.meta_field_processor.recreate_bind_group(
             &self.gpu.device,
@@ -332,32 +801,34 @@ impl Background {
         self.meta_field_renderer
             .recreate_bind_group(&self.gpu.device, &self.meta_field);
 
-        self.marching_squares_processor.recreate_bind_group(
+        self.liquid_renderer.recreate_bind_group(
             &self.gpu.device,
+            &self.frame_metadata,
             &self.meta_field,
-            &self.quads,
+            self.blur.output_view(),
         );
 
-        self.marching_squares_shape_renderer.recreate_bind_group(
-            &self.gpu.device,
-            &self.frame_metadata,
-            &self.quads,
-        );
+        if let Some(outline) = &mut self.outline {
+            outline.resize(&self.gpu.device, &self.frame_metadata, &self.meta_field);
+        }
 
-        self.marching_squares_liquid_quad_renderer
-            .recreate_bind_group(
-                &self.gpu.device,
-                &self.frame_metadata,
-                &self.quads,
-                &self.meta_field,
-                self.blur.output_view(),
-            );
+        if let Some(contours) = &mut self.contours {
+            contours.resize(&self.gpu.device, &self.frame_metadata, &self.meta_field);
+        }
+
+        self.post_effects
+            .resize(&self.gpu.device, &self.frame_metadata, &self.meta_field);
     }
 
     fn handle_mouse_move(&mut self, pos: IVec2) {
         self.mouse.set_target(pos.as_vec2());
     }
 
+    fn handle_ripple(&mut self, pos: IVec2) {
+        let page_pos = pos + self.frame_metadata.top_left();
+        self.impulses.push(Impulse::ripple(page_pos.as_vec2()));
+    }
+
     fn handle_update(&mut self, delta_time: f32) {
         self.frame_metadata.update(
             &self.gpu.queue,
@@ -369,12 +840,21 @@ impl Background {
 
         self.mouse.update(&self.frame_metadata, delta_time);
 
-        // self.grid_processor.update_target(
-        //     &self.gpu.queue,
-        //     &self.frame_metadata,
-        //     &self.mouse,
-        //     delta_time,
-        // );
+        self.light
+            .update(&self.frame_metadata, &self.mouse, delta_time);
+        self.liquid_renderer
+            .set_light(&self.gpu.queue, &self.light.uniform(&self.frame_metadata));
+
+        self.impulses.update(&self.gpu.queue, delta_time);
+
+        if let Some(grid) = &mut self.grid {
+            grid.processor.update_target(
+                &self.gpu.queue,
+                &self.frame_metadata,
+                &self.mouse,
+                delta_time,
+            );
+        }
 
         self.panel_controller
             .update(&mut self.meta_shapes, delta_time);
@@ -385,11 +865,44 @@ impl Background {
             hidden: if self.mouse.hidden() { 1 } else { 0 },
         };
 
+        self.skills_controller.update(&self.mouse, delta_time);
+        self.meta_shapes.balls_mut()[1] = self.skills_controller.hover_ball(&self.frame_metadata);
+
         // TODO: Update only if needed
-        self.meta_shapes.ensure_buffer(&self.gpu.queue);
+        self.meta_shapes
+            .ensure_buffer_displaced(&self.gpu.queue, &self.impulses);
+
+        if !self.svg_export_requests.is_empty() {
+            let svg =
+                svg_export::export(&self.meta_shapes).map_or(JsValue::UNDEFINED, JsValue::from);
+            for resolve in self.svg_export_requests.drain(..) {
+                if let Err(e) = resolve.call1(&JsValue::NULL, &svg) {
+                    log::error!("Failed to resolve liquid SVG export: {e:?}");
+                }
+            }
+        }
+
+        if let Some(material) = self.liquid_material.update(delta_time) {
+            self.liquid_renderer
+                .set_material(&self.gpu.queue, &material);
+        }
+
+        self.post_effects.update(&self.gpu.queue, delta_time);
 
-        self.background_controller.update(&self.frame_metadata);
-        self.skills_controller.update();
+        self.layers.update(
+            &self.gpu.device,
+            &self.gpu.queue,
+            &self.frame_metadata,
+            delta_time,
+        );
+
+        if let Some(outline) = &mut self.outline {
+            outline.update_theme(&self.gpu.queue);
+        }
+
+        if let Some(contours) = &mut self.contours {
+            contours.update_theme(&self.gpu.queue);
+        }
     }
 
     fn should_render(&self) -> bool {
@@ -436,56 +949,60 @@ impl Background {
                 });
             }
 
-            self.background_renderer.render(
+            self.layers.render(
                 &self.gpu.device,
                 &self.gpu.queue,
                 &mut encoder,
+                &mut self.vello_renderer,
                 &view,
                 &self.frame_metadata,
-                self.background_controller.position().as_ivec2(),
             );
 
-            self.skills_renderer.render(
-                &self.gpu.device,
-                &self.gpu.queue,
-                &mut encoder,
-                &view,
-                &self.frame_metadata,
-                self.skills_controller.top_left().as_ivec2(),
-                self.skills_controller.bottom_right().as_ivec2(),
-            );
+            if let Some(grid) = &self.grid {
+                grid.processor
+                    .process(&mut encoder, grid.metadata.resolution());
 
-            // self.grid_processor
-            //     .process(&mut encoder, self.grid_metadata.resolution());
-
-            // self.grid_renderer
-            //     .render(&mut encoder, &view, self.grid_metadata.resolution());
+                grid.renderer
+                    .render(&mut encoder, &view, grid.metadata.resolution());
+            }
 
             self.meta_field_processor
                 .process(&mut encoder, self.meta_field.resolution());
 
             // self.meta_field_renderer.render(&mut encoder, &view);
 
-            self.marching_squares_processor.process(
+            self.liquid_renderer.process(
                 &self.gpu.queue,
                 &mut encoder,
                 self.meta_field.resolution(),
             );
 
-            self.blur.blur(
-                &self.gpu.device,
-                &self.gpu.queue,
-                &mut encoder,
-                &self
-                    .background
-                    .create_view(&wgpu::TextureViewDescriptor::default()),
-            );
+            if let Some(outline) = &self.outline {
+                outline.processor.process(
+                    &self.gpu.queue,
+                    &mut encoder,
+                    self.meta_field.resolution(),
+                );
+            }
 
-            // self.marching_squares_shape_renderer.render(
-            //     &mut encoder,
-            //     &view,
-            //     &self.marching_squares_processor,
-            // );
+            self.blur.blur(&mut encoder);
+
+            // if let LiquidRenderer::Quad {
+            //     processor,
+            //     shape_renderer,
+            //     ..
+            // } = &self.liquid_renderer
+            // {
+            //     shape_renderer.render(&mut encoder, &view, processor);
+            // }
+
+            if let Some(contours) = &self.contours {
+                contours.processor.process(
+                    &self.gpu.queue,
+                    &mut encoder,
+                    self.meta_field.resolution(),
+                );
+            }
         }
 
         // Render to screen
@@ -497,10 +1014,16 @@ impl Background {
                     return;
                 }
             };
-            let view = texture
+            let surface_view = texture
                 .texture
                 .create_view(&wgpu::TextureViewDescriptor::default());
 
+            // Composite into the post effect chain instead when it has anything to do.
+            let view = match self.post_effects.is_active() {
+                true => self.post_effects.input_view(),
+                false => &surface_view,
+            };
+
             self.surface_blitter.copy_full(
                 &self.gpu.device,
                 &self.gpu.queue,
@@ -508,15 +1031,27 @@ impl Background {
                 &self
                     .background
                     .create_view(&wgpu::TextureViewDescriptor::default()),
-                &view,
+                view,
                 &self.frame_metadata,
             );
 
-            self.marching_squares_liquid_quad_renderer.render(
-                &mut encoder,
-                &view,
-                &self.marching_squares_processor,
-            );
+            self.liquid_renderer.render(&mut encoder, view);
+
+            if let Some(contours) = &self.contours {
+                contours
+                    .renderer
+                    .render(&mut encoder, view, &contours.processor);
+            }
+
+            if let Some(outline) = &self.outline {
+                outline
+                    .renderer
+                    .render(&mut encoder, view, &outline.processor);
+            }
+
+            if self.post_effects.is_active() {
+                self.post_effects.render(&mut encoder, &surface_view);
+            }
 
             self.gpu.queue.submit(Some(encoder.finish()));
             texture.present();
@@ -0,0 +1,675 @@
+use glam::*;
+use wgpu::util::DeviceExt as _;
+
+use crate::{
+    config::{BlurConfig, BlurMode},
+    frame::FrameMetadata,
+};
+
+/// Largest radius of [`GaussianBlurPipeline`], must match `gaussian_blur.wgsl`.
+pub const MAX_GAUSSIAN_RADIUS: f32 = 64.0;
+
+/// Most downsample levels of [`DualKawaseBlurPipeline`].
+pub const MAX_KAWASE_LEVELS: u32 = 6;
+
+/// Blurs the background for the frosted liquid.
+///
+/// Both modes cache their bind groups for the input view given to [`BlurPipeline::new`] and
+/// [`BlurPipeline::resize`], so the input texture must be recreated only together with a resize.
+#[derive(Debug)]
+pub enum BlurPipeline {
+    Gaussian(GaussianBlurPipeline),
+    DualKawase(DualKawaseBlurPipeline),
+}
+
+impl BlurPipeline {
+    pub fn new(
+        config: &BlurConfig,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_view: &wgpu::TextureView,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        match config.mode {
+            BlurMode::Gaussian => Self::Gaussian(GaussianBlurPipeline::new(
+                device,
+                frame_metadata,
+                input_view,
+                config.radius,
+                texture_format,
+            )),
+            BlurMode::DualKawase => Self::DualKawase(DualKawaseBlurPipeline::new(
+                device,
+                frame_metadata,
+                input_view,
+                config.radius,
+                texture_format,
+            )),
+        }
+    }
+
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_view: &wgpu::TextureView,
+    ) {
+        match self {
+            Self::Gaussian(blur) => blur.resize(device, frame_metadata, input_view),
+            Self::DualKawase(blur) => blur.resize(device, frame_metadata, input_view),
+        }
+    }
+
+    pub fn set_radius(&mut self, queue: &wgpu::Queue, radius: f32) {
+        match self {
+            Self::Gaussian(blur) => blur.set_radius(queue, radius),
+            Self::DualKawase(blur) => blur.set_radius(queue, radius),
+        }
+    }
+
+    pub fn blur(&self, encoder: &mut wgpu::CommandEncoder) {
+        match self {
+            Self::Gaussian(blur) => blur.blur(encoder),
+            Self::DualKawase(blur) => blur.blur(encoder),
+        }
+    }
+
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        match self {
+            Self::Gaussian(blur) => blur.output_view(),
+            Self::DualKawase(blur) => blur.output_view(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct GaussianBlurParams {
+    direction: Vec2,
+    radius: f32,
+    padding: f32,
+}
+
+/// Separable Gaussian at full resolution, with `sigma = radius / 3`.
+#[derive(Debug)]
+pub struct GaussianBlurPipeline {
+    render_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    params_buffer_x: wgpu::Buffer,
+    params_buffer_y: wgpu::Buffer,
+    ping: BlurTarget,
+    pong: BlurTarget,
+    bind_group_x: wgpu::BindGroup,
+    bind_group_y: wgpu::BindGroup,
+    texture_format: wgpu::TextureFormat,
+}
+
+impl GaussianBlurPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_view: &wgpu::TextureView,
+        radius: f32,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Gaussian Blur Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shader/gaussian_blur.wgsl").into()),
+        });
+
+        let bind_group_layout = create_bind_group_layout(device, "Gaussian Blur Bind Group Layout");
+
+        let render_pipeline = create_render_pipeline(
+            device,
+            &bind_group_layout,
+            &shader_module,
+            "frag_main",
+            texture_format,
+            "Gaussian Blur",
+        );
+
+        let sampler = create_sampler(device, "Gaussian Blur Sampler");
+
+        let radius = radius.clamp(0.0, MAX_GAUSSIAN_RADIUS);
+
+        let params_buffer_x = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Gaussian Blur Params Buffer X"),
+            contents: bytemuck::bytes_of(&GaussianBlurParams {
+                direction: Vec2::X,
+                radius,
+                ..Default::default()
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let params_buffer_y = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Gaussian Blur Params Buffer Y"),
+            contents: bytemuck::bytes_of(&GaussianBlurParams {
+                direction: Vec2::Y,
+                radius,
+                ..Default::default()
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let ping = create_target(
+            device,
+            frame_metadata.resolution(),
+            texture_format,
+            "Gaussian Blur Ping Texture",
+        );
+
+        let pong = create_target(
+            device,
+            frame_metadata.resolution(),
+            texture_format,
+            "Gaussian Blur Pong Texture",
+        );
+
+        let bind_group_x = create_bind_group(
+            device,
+            &bind_group_layout,
+            input_view,
+            &sampler,
+            &params_buffer_x,
+            "Gaussian Blur X Bind Group",
+        );
+
+        let bind_group_y = create_bind_group(
+            device,
+            &bind_group_layout,
+            &ping.view,
+            &sampler,
+            &params_buffer_y,
+            "Gaussian Blur Y Bind Group",
+        );
+
+        Self {
+            render_pipeline,
+            bind_group_layout,
+            sampler,
+            params_buffer_x,
+            params_buffer_y,
+            ping,
+            pong,
+            bind_group_x,
+            bind_group_y,
+            texture_format,
+        }
+    }
+
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_view: &wgpu::TextureView,
+    ) {
+        self.ping = create_target(
+            device,
+            frame_metadata.resolution(),
+            self.texture_format,
+            "Gaussian Blur Ping Texture",
+        );
+
+        self.pong = create_target(
+            device,
+            frame_metadata.resolution(),
+            self.texture_format,
+            "Gaussian Blur Pong Texture",
+        );
+
+        self.bind_group_x = create_bind_group(
+            device,
+            &self.bind_group_layout,
+            input_view,
+            &self.sampler,
+            &self.params_buffer_x,
+            "Gaussian Blur X Bind Group",
+        );
+
+        self.bind_group_y = create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.ping.view,
+            &self.sampler,
+            &self.params_buffer_y,
+            "Gaussian Blur Y Bind Group",
+        );
+    }
+
+    pub fn set_radius(&mut self, queue: &wgpu::Queue, radius: f32) {
+        let radius = radius.clamp(0.0, MAX_GAUSSIAN_RADIUS);
+
+        for (buffer, direction) in [
+            (&self.params_buffer_x, Vec2::X),
+            (&self.params_buffer_y, Vec2::Y),
+        ] {
+            queue.write_buffer(
+                buffer,
+                0,
+                bytemuck::bytes_of(&GaussianBlurParams {
+                    direction,
+                    radius,
+                    ..Default::default()
+                }),
+            );
+        }
+    }
+
+    pub fn blur(&self, encoder: &mut wgpu::CommandEncoder) {
+        run_pass(
+            encoder,
+            &self.render_pipeline,
+            &self.bind_group_x,
+            &self.ping.view,
+            "Gaussian Blur X Render Pass",
+        );
+        run_pass(
+            encoder,
+            &self.render_pipeline,
+            &self.bind_group_y,
+            &self.pong.view,
+            "Gaussian Blur Y Render Pass",
+        );
+    }
+
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        &self.pong.view
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct DualKawaseBlurParams {
+    offset: f32,
+    padding: Vec3,
+}
+
+/// Dual Kawase blur, downsampling by half per level then upsampling back.
+///
+/// Much cheaper than [`GaussianBlurPipeline`] for large radii, since most passes run at a
+/// fraction of the resolution.
+#[derive(Debug)]
+pub struct DualKawaseBlurPipeline {
+    down_pipeline: wgpu::RenderPipeline,
+    up_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    params_buffer: wgpu::Buffer,
+    levels: Vec<BlurTarget>,
+    output: BlurTarget,
+    /// The input, then every level, as a pass source.
+    bind_groups: Vec<wgpu::BindGroup>,
+    level_count: u32,
+    texture_format: wgpu::TextureFormat,
+}
+
+impl DualKawaseBlurPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_view: &wgpu::TextureView,
+        radius: f32,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Dual Kawase Blur Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shader/dual_kawase_blur.wgsl").into()),
+        });
+
+        let bind_group_layout =
+            create_bind_group_layout(device, "Dual Kawase Blur Bind Group Layout");
+
+        let down_pipeline = create_render_pipeline(
+            device,
+            &bind_group_layout,
+            &shader_module,
+            "frag_down",
+            texture_format,
+            "Dual Kawase Blur Down",
+        );
+
+        let up_pipeline = create_render_pipeline(
+            device,
+            &bind_group_layout,
+            &shader_module,
+            "frag_up",
+            texture_format,
+            "Dual Kawase Blur Up",
+        );
+
+        let sampler = create_sampler(device, "Dual Kawase Blur Sampler");
+
+        let (level_count, offset) = Self::levels(radius);
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Dual Kawase Blur Params Buffer"),
+            contents: bytemuck::bytes_of(&DualKawaseBlurParams {
+                offset,
+                ..Default::default()
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let (levels, output, bind_groups) = Self::create_targets(
+            device,
+            frame_metadata,
+            input_view,
+            &bind_group_layout,
+            &sampler,
+            &params_buffer,
+            texture_format,
+        );
+
+        Self {
+            down_pipeline,
+            up_pipeline,
+            bind_group_layout,
+            sampler,
+            params_buffer,
+            levels,
+            output,
+            bind_groups,
+            level_count,
+            texture_format,
+        }
+    }
+
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_view: &wgpu::TextureView,
+    ) {
+        (self.levels, self.output, self.bind_groups) = Self::create_targets(
+            device,
+            frame_metadata,
+            input_view,
+            &self.bind_group_layout,
+            &self.sampler,
+            &self.params_buffer,
+            self.texture_format,
+        );
+    }
+
+    pub fn set_radius(&mut self, queue: &wgpu::Queue, radius: f32) {
+        let (level_count, offset) = Self::levels(radius);
+        self.level_count = level_count;
+
+        queue.write_buffer(
+            &self.params_buffer,
+            0,
+            bytemuck::bytes_of(&DualKawaseBlurParams {
+                offset,
+                ..Default::default()
+            }),
+        );
+    }
+
+    pub fn blur(&self, encoder: &mut wgpu::CommandEncoder) {
+        let level_count = self.level_count as usize;
+
+        for level in 0..level_count {
+            run_pass(
+                encoder,
+                &self.down_pipeline,
+                &self.bind_groups[level],
+                &self.levels[level].view,
+                "Dual Kawase Blur Down Render Pass",
+            );
+        }
+
+        for level in (0..level_count).rev() {
+            let target = match level {
+                0 => &self.output.view,
+                _ => &self.levels[level - 1].view,
+            };
+
+            run_pass(
+                encoder,
+                &self.up_pipeline,
+                &self.bind_groups[level + 1],
+                target,
+                "Dual Kawase Blur Up Render Pass",
+            );
+        }
+    }
+
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        &self.output.view
+    }
+
+    /// Each level roughly doubles the blur, the sample offset covers the rest of the radius.
+    fn levels(radius: f32) -> (u32, f32) {
+        let radius = radius.max(1.0);
+        let level_count = ((radius * 0.5).log2().ceil() as u32).clamp(1, MAX_KAWASE_LEVELS);
+        let offset = radius / (1 << (level_count + 1)) as f32;
+
+        (level_count, offset)
+    }
+
+    fn create_targets(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_view: &wgpu::TextureView,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        params_buffer: &wgpu::Buffer,
+        texture_format: wgpu::TextureFormat,
+    ) -> (Vec<BlurTarget>, BlurTarget, Vec<wgpu::BindGroup>) {
+        let levels = (1..=MAX_KAWASE_LEVELS)
+            .map(|level| {
+                create_target(
+                    device,
+                    (frame_metadata.resolution() >> level).max(UVec2::ONE),
+                    texture_format,
+                    "Dual Kawase Blur Level Texture",
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let output = create_target(
+            device,
+            frame_metadata.resolution(),
+            texture_format,
+            "Dual Kawase Blur Output Texture",
+        );
+
+        let bind_groups = std::iter::once(input_view)
+            .chain(levels.iter().map(|level| &level.view))
+            .map(|view| {
+                create_bind_group(
+                    device,
+                    bind_group_layout,
+                    view,
+                    sampler,
+                    params_buffer,
+                    "Dual Kawase Blur Bind Group",
+                )
+            })
+            .collect();
+
+        (levels, output, bind_groups)
+    }
+}
+
+fn create_bind_group_layout(device: &wgpu::Device, label: &str) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(label),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    })
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    params_buffer: &wgpu::Buffer,
+    label: &str,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(label),
+        layout: bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    shader_module: &wgpu::ShaderModule,
+    fragment_entry_point: &str,
+    texture_format: wgpu::TextureFormat,
+    label: &str,
+) -> wgpu::RenderPipeline {
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(&format!("{label} Pipeline Layout")),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(&format!("{label} Render Pipeline")),
+        layout: Some(&render_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader_module,
+            entry_point: Some("vert_main"),
+            buffers: &[],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader_module,
+            entry_point: Some(fragment_entry_point),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: texture_format,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+fn create_sampler(device: &wgpu::Device, label: &str) -> wgpu::Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some(label),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Nearest,
+        ..Default::default()
+    })
+}
+
+/// A blur pass output, the view keeps its texture alive.
+#[derive(Debug)]
+struct BlurTarget {
+    view: wgpu::TextureView,
+}
+
+fn create_target(
+    device: &wgpu::Device,
+    size: UVec2,
+    texture_format: wgpu::TextureFormat,
+    label: &str,
+) -> BlurTarget {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: texture_format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    BlurTarget { view }
+}
+
+fn run_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    render_pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+    dst_view: &wgpu::TextureView,
+    label: &str,
+) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: dst_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: wgpu::StoreOp::Store,
+            },
+            depth_slice: None,
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    });
+
+    render_pass.set_pipeline(render_pipeline);
+    render_pass.set_bind_group(0, bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
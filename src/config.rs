@@ -0,0 +1,322 @@
+use std::{fmt::Display, str::FromStr};
+
+use glam::*;
+
+use crate::{
+    grid::{GRID_CELL_SIZE, GRID_DAMPING, GRID_STIFFNESS},
+    light::{LIGHT_HEIGHT, LIGHT_INTENSITY},
+    liquid_material::LiquidMaterialPreset,
+    mar_sq::contour_line::{ContourChannel, MAX_CONTOUR_COUNT},
+    post_effect::PostEffectKind,
+    theme::ThemePropertyName,
+};
+
+/// How the liquid is rasterised.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum LiquidRenderMode {
+    /// Marching squares into a quad buffer, then an indirect draw of the quads.
+    #[default]
+    Quad,
+    /// A single full screen pass classifying the field cells per pixel.
+    Fused,
+    /// Marching squares into a triangle mesh, drawn with a flat fill.
+    Mesh,
+    /// No liquid, for showing only the outline.
+    None,
+}
+
+/// Anti-aliased contour lines drawn over the liquid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutlineConfig {
+    pub width: f32,
+    pub color: ThemePropertyName,
+}
+
+impl Default for OutlineConfig {
+    fn default() -> Self {
+        Self {
+            width: 2.0,
+            color: ThemePropertyName::Foreground,
+        }
+    }
+}
+
+/// Topographic contour lines of a meta field channel, drawn over the liquid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourConfig {
+    pub channel: ContourChannel,
+    pub count: u32,
+    pub width: f32,
+}
+
+impl Default for ContourConfig {
+    fn default() -> Self {
+        Self {
+            channel: ContourChannel::Magnitude,
+            count: 5,
+            width: 1.5,
+        }
+    }
+}
+
+/// The spring grid layer drawn over the background image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridConfig {
+    pub cell_size: u32,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            cell_size: GRID_CELL_SIZE,
+            stiffness: GRID_STIFFNESS,
+            damping: GRID_DAMPING,
+        }
+    }
+}
+
+/// How the background behind the liquid is blurred.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BlurMode {
+    /// Separable Gaussian at full resolution, up to [`crate::blur::MAX_GAUSSIAN_RADIUS`].
+    #[default]
+    Gaussian,
+    /// Downsample and upsample chain, cheaper for large radii.
+    DualKawase,
+}
+
+/// The frosted look of the liquid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlurConfig {
+    pub mode: BlurMode,
+    pub radius: f32,
+}
+
+impl Default for BlurConfig {
+    fn default() -> Self {
+        Self {
+            mode: BlurMode::Gaussian,
+            radius: 12.0,
+        }
+    }
+}
+
+/// The point light the liquid is shaded with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightConfig {
+    /// Pinned position in viewport pixels, follows the mouse when `None`.
+    pub position: Option<Vec2>,
+    pub height: f32,
+    pub intensity: f32,
+    /// Linear RGB, each channel from 0 to 1.
+    pub color: Vec3,
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        Self {
+            position: None,
+            height: LIGHT_HEIGHT,
+            intensity: LIGHT_INTENSITY,
+            color: Vec3::ONE,
+        }
+    }
+}
+
+/// Background options, read from the URL search parameters.
+#[derive(Debug, Default, Clone)]
+pub struct BackgroundConfig {
+    pub liquid_render_mode: LiquidRenderMode,
+    pub liquid_material: LiquidMaterialPreset,
+    /// Per channel refraction with caustic highlights, the mesh renderer has no shading.
+    pub dispersion: bool,
+    pub light: LightConfig,
+    pub outline: Option<OutlineConfig>,
+    pub contours: Option<ContourConfig>,
+    pub grid: Option<GridConfig>,
+    pub blur: BlurConfig,
+    /// Post effects in the order they are applied.
+    pub post_effects: Vec<PostEffectKind>,
+}
+
+impl BackgroundConfig {
+    /// Reads the following parameters:
+    /// - `liquid=quad|fused|mesh|none`
+    /// - `material=clear-glass|frosted|tinted|water`
+    /// - `dispersion`
+    /// - `light-position=<x>,<y>`, `light-height=<px>`, `light-intensity=<k>` and
+    ///   `light-color=<r>,<g>,<b>`, the old `light=<x>,<y>,<z>` direction is no longer read
+    /// - `outline=<width>` and `outline-color=<theme property>`
+    /// - `contours=<count>` up to [`MAX_CONTOUR_COUNT`], `contour-channel=magnitude|elevation` and
+    ///   `contour-width=<width>`
+    /// - `grid`, `grid-cell-size=<px>`, `grid-stiffness=<k>` and `grid-damping=<c>`
+    /// - `blur=gaussian|dual-kawase` and `blur-radius=<px>`
+    /// - `post=<effect>,...` with `bloom`, `vignette`, `film-grain` or `chromatic-aberration`
+    pub fn from_params(params: &web_sys::UrlSearchParams) -> Self {
+        let mut config = Self::default();
+
+        if let Some(mode) = parse_param(params, "liquid") {
+            config.liquid_render_mode = mode;
+        }
+
+        if let Some(preset) = parse_param(params, "material") {
+            config.liquid_material = preset;
+        }
+
+        config.dispersion = params.has("dispersion");
+
+        if params.has("light") {
+            log::warn!("The light parameter is no longer read, use light-position instead");
+        }
+
+        if let Some(position) = params
+            .get("light-position")
+            .filter(|position| !position.is_empty())
+        {
+            match parse_vec2(&position) {
+                Some(position) => config.light.position = Some(position),
+                None => log::error!("Invalid light-position parameter {position:?}"),
+            }
+        }
+
+        if let Some(height) = parse_param::<f32>(params, "light-height") {
+            config.light.height = height.max(1.0);
+        }
+
+        if let Some(intensity) = parse_param::<f32>(params, "light-intensity") {
+            config.light.intensity = intensity.max(0.0);
+        }
+
+        if let Some(color) = params.get("light-color").filter(|color| !color.is_empty()) {
+            match parse_vec3(&color) {
+                Some(color) => config.light.color = color.clamp(Vec3::ZERO, Vec3::ONE),
+                None => log::error!("Invalid light-color parameter {color:?}"),
+            }
+        }
+
+        if params.has("outline") {
+            let mut outline = OutlineConfig::default();
+
+            if let Some(width) = parse_non_negative(params, "outline") {
+                outline.width = width;
+            }
+
+            if let Some(color) = parse_param(params, "outline-color") {
+                outline.color = color;
+            }
+
+            config.outline = Some(outline);
+        }
+
+        if params.has("contours") {
+            let mut contours = ContourConfig::default();
+
+            if let Some(count) = parse_param::<u32>(params, "contours") {
+                contours.count = count.clamp(1, MAX_CONTOUR_COUNT);
+            }
+
+            if let Some(channel) = parse_param(params, "contour-channel") {
+                contours.channel = channel;
+            }
+
+            if let Some(width) = parse_param(params, "contour-width") {
+                contours.width = width;
+            }
+
+            config.contours = Some(contours);
+        }
+
+        if params.has("grid") {
+            let mut grid = GridConfig::default();
+
+            if let Some(cell_size) = parse_param::<u32>(params, "grid-cell-size") {
+                grid.cell_size = cell_size.max(1);
+            }
+
+            if let Some(stiffness) = parse_non_negative(params, "grid-stiffness") {
+                grid.stiffness = stiffness;
+            }
+
+            if let Some(damping) = parse_non_negative(params, "grid-damping") {
+                grid.damping = damping;
+            }
+
+            config.grid = Some(grid);
+        }
+
+        if let Some(mode) = parse_param(params, "blur") {
+            config.blur.mode = mode;
+        }
+
+        if let Some(radius) = parse_param::<f32>(params, "blur-radius") {
+            config.blur.radius = radius.max(0.0);
+        }
+
+        if let Some(post) = params.get("post") {
+            config.post_effects = post
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .filter_map(|name| match name.parse() {
+                    Ok(kind) => Some(kind),
+                    Err(e) => {
+                        log::error!("Invalid post effect {name:?}: {e}");
+                        None
+                    }
+                })
+                .collect();
+        }
+
+        config
+    }
+}
+
+/// Parses a non-empty parameter, logging the error if it is invalid.
+fn parse_param<T>(params: &web_sys::UrlSearchParams, name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = params.get(name).filter(|value| !value.is_empty())?;
+
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::error!("Invalid {name} parameter {value:?}: {e}");
+            None
+        }
+    }
+}
+
+/// Parses a finite parameter clamped to zero, logging the error if it is invalid.
+fn parse_non_negative(params: &web_sys::UrlSearchParams, name: &str) -> Option<f32> {
+    let value = parse_param::<f32>(params, name)?;
+
+    if !value.is_finite() {
+        log::error!("Invalid {name} parameter {value:?}: not finite");
+        return None;
+    }
+
+    Some(value.max(0.0))
+}
+
+/// Parses `x,y`.
+fn parse_vec2(value: &str) -> Option<Vec2> {
+    let (x, y) = value.split_once(',')?;
+    Some(Vec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Parses `x,y,z`.
+fn parse_vec3(value: &str) -> Option<Vec3> {
+    let mut components = value.split(',').map(|c| c.trim().parse().ok());
+    let vec = Vec3::new(
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    components.next().is_none().then_some(vec)
+}
@@ -8,8 +8,9 @@ use crate::{
     add_event_listener,
     ext::{DomRectExt as _, HtmlCollectionExt as _, WindowExt as _},
     frame::FrameMetadata,
-    meta_shape::{MetaBox, MetaShapes},
-    pipeline::{BackgroundImageRenderer, RADIUS},
+    meta_shape::{MetaBall, MetaBox, MetaShapes},
+    mouse::Mouse,
+    pipeline::{BackgroundImageRenderer, RADIUS, SvgElement},
 };
 
 const HOVER_OFFSET: f32 = 8.0; // Has to match the CSS value
@@ -350,50 +351,58 @@ impl PanelController {
     }
 }
 
+/// Follows the rect of an element, lagging behind the page scroll by the parallax factor.
 #[derive(Debug)]
-pub struct BackgroundController {
-    project_element: web_sys::HtmlElement,
-    position: Vec2,
+pub struct LayerController {
+    element: web_sys::HtmlElement,
+    parallax: f32,
+    top_left: Vec2,
+    bottom_right: Vec2,
 }
 
-impl BackgroundController {
-    pub fn new() -> Self {
-        let window = web_sys::window().expect_throw("window");
-        let document = window.document().expect_throw("document");
-
-        let project_element = document
-            .get_element_by_id("projects")
-            .unwrap_throw()
-            .dyn_into::<web_sys::HtmlElement>()
-            .unwrap_throw();
-
-        let project_rect = project_element.get_bounding_client_rect();
+impl LayerController {
+    pub fn new(element: web_sys::HtmlElement, parallax: f32) -> Self {
+        let rect = element.get_bounding_client_rect();
 
         Self {
-            project_element,
-            position: Vec2::new(0.0, project_rect.top() as f32),
+            element,
+            parallax,
+            top_left: rect.top_left(),
+            bottom_right: rect.bottom_right(),
         }
     }
 
     pub fn update(&mut self, frame_metadata: &FrameMetadata) {
-        let project_rect = self.project_element.get_bounding_client_rect();
+        let rect = self.element.get_bounding_client_rect();
+        let offset = Vec2::Y * frame_metadata.top_left().y as f32 * self.parallax;
 
-        self.position = Vec2::new(
-            0.0,
-            project_rect.top() as f32 + frame_metadata.top_left().y as f32 * 0.2,
-        );
+        self.top_left = rect.top_left() + offset;
+        self.bottom_right = rect.bottom_right() + offset;
+    }
+
+    pub fn top_left(&self) -> Vec2 {
+        self.top_left
     }
 
-    pub fn position(&self) -> Vec2 {
-        self.position
+    pub fn bottom_right(&self) -> Vec2 {
+        self.bottom_right
     }
 }
 
+/// Size of the ball over the hovered icon relative to the icon.
+const SKILL_HOVER_BALL_SCALE: f32 = 0.4;
+const SKILL_HOVER_BALL_SPEED: f32 = 12.0;
+
 #[derive(Debug)]
 pub struct SkillsController {
     pub element: web_sys::HtmlElement,
     pub top_left: Vec2,
     pub bottom_right: Vec2,
+    icons: Vec<SvgElement>,
+    hovered: Option<usize>,
+    hover_center: Vec2,
+    hover_radius: f32,
+    tooltip: web_sys::HtmlElement,
 }
 
 impl SkillsController {
@@ -409,24 +418,102 @@ impl SkillsController {
 
         let rect = element.get_bounding_client_rect();
 
+        let tooltip = document
+            .create_element("div")
+            .expect_throw("tooltip element")
+            .dyn_into::<web_sys::HtmlElement>()
+            .unwrap_throw();
+        tooltip.set_class_name("skill-tooltip");
+        tooltip.set_hidden(true);
+        document
+            .body()
+            .expect_throw("body")
+            .append_child(&tooltip)
+            .expect_throw("append tooltip");
+
         Self {
             element,
             top_left: rect.top_left(),
             bottom_right: rect.bottom_right(),
+            icons: Vec::new(),
+            hovered: None,
+            hover_center: Vec2::ZERO,
+            hover_radius: 0.0,
+            tooltip,
         }
     }
 
-    pub fn update(&mut self) {
+    /// Sets the hoverable icons, from the elements of the skills SVG.
+    pub fn set_icons(&mut self, elements: &[SvgElement]) {
+        self.icons = elements.to_vec();
+    }
+
+    pub fn update(&mut self, mouse: &Mouse, delta_time: f32) {
         let rect = self.element.get_bounding_client_rect();
         self.top_left = rect.top_left();
         self.bottom_right = rect.bottom_right();
+
+        let hovered = if mouse.hidden() {
+            None
+        } else {
+            (0..self.icons.len()).find(|&i| {
+                let (min, max) = self.icon_rect(i);
+                mouse.target().cmpge(min).all() && mouse.target().cmple(max).all()
+            })
+        };
+
+        if hovered != self.hovered {
+            self.hovered = hovered;
+
+            match hovered {
+                Some(i) => {
+                    let icon = &self.icons[i];
+                    let name = icon.name.as_deref().unwrap_or(&icon.id);
+                    self.tooltip.set_text_content(Some(name));
+                    self.tooltip.set_hidden(false);
+                }
+                None => self.tooltip.set_hidden(true),
+            }
+        }
+
+        let target_radius = match self.hovered {
+            Some(i) => {
+                let (min, max) = self.icon_rect(i);
+                self.hover_center = (min + max) * 0.5;
+
+                let style = self.tooltip.style();
+                style
+                    .set_property("left", &format!("{}px", self.hover_center.x))
+                    .expect_throw("tooltip left");
+                style
+                    .set_property("top", &format!("{}px", min.y))
+                    .expect_throw("tooltip top");
+
+                (max - min).min_element() * SKILL_HOVER_BALL_SCALE
+            }
+            None => 0.0,
+        };
+
+        self.hover_radius +=
+            (target_radius - self.hover_radius) * (delta_time * SKILL_HOVER_BALL_SPEED).min(1.0);
     }
 
-    pub fn top_left(&self) -> Vec2 {
-        self.top_left
+    /// The meta ball rising under the hovered icon.
+    pub fn hover_ball(&self, frame_metadata: &FrameMetadata) -> MetaBall {
+        MetaBall {
+            center: self.hover_center + frame_metadata.top_left().as_vec2(),
+            radius: self.hover_radius,
+            hidden: if self.hover_radius < 0.5 { 1 } else { 0 },
+        }
     }
 
-    pub fn bottom_right(&self) -> Vec2 {
-        self.bottom_right
+    /// Viewport rect of an icon.
+    fn icon_rect(&self, index: usize) -> (Vec2, Vec2) {
+        let size = self.bottom_right - self.top_left;
+        let icon = &self.icons[index];
+        (
+            self.top_left + icon.min * size,
+            self.top_left + icon.max * size,
+        )
     }
 }
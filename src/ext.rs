@@ -77,6 +77,16 @@ impl HtmlCollectionExt for web_sys::HtmlCollection {
     }
 }
 
+pub trait NodeListExt {
+    fn iter(&self) -> impl Iterator<Item = web_sys::Node>;
+}
+
+impl NodeListExt for web_sys::NodeList {
+    fn iter(&self) -> impl Iterator<Item = web_sys::Node> {
+        (0..self.length()).filter_map(|i| self.item(i))
+    }
+}
+
 pub trait DomRectExt {
     fn top_left(&self) -> Vec2;
 
@@ -8,81 +8,126 @@ pub mod pipeline;
 pub mod target;
 
 pub const GRID_CELL_SIZE: u32 = 48;
+pub const GRID_STIFFNESS: f32 = 20.0;
+pub const GRID_DAMPING: f32 = 4.0;
 
 #[derive(Debug)]
-pub struct GridMetadata(FrameMetadata);
+pub struct GridMetadata {
+    metadata: FrameMetadata,
+    cell_size: u32,
+}
 
 impl GridMetadata {
-    pub fn new(device: &wgpu::Device, frame_metadata: &FrameMetadata) -> Self {
-        Self(FrameMetadata::new(
-            device,
-            frame_metadata.resolution() / GRID_CELL_SIZE + UVec2::splat(2),
-            -IVec2::splat(GRID_CELL_SIZE as i32) / 2,
-        ))
+    pub fn new(device: &wgpu::Device, frame_metadata: &FrameMetadata, cell_size: u32) -> Self {
+        Self {
+            metadata: FrameMetadata::new(
+                device,
+                frame_metadata.resolution() / cell_size + UVec2::splat(2),
+                -IVec2::splat(cell_size as i32) / 2,
+            ),
+            cell_size,
+        }
     }
 
     pub fn update(&mut self, queue: &wgpu::Queue, frame_metadata: &FrameMetadata) {
-        self.0.update(
+        self.metadata.update(
             queue,
-            frame_metadata.resolution() / GRID_CELL_SIZE + UVec2::splat(2),
-            -IVec2::splat(GRID_CELL_SIZE as i32) / 2,
+            frame_metadata.resolution() / self.cell_size + UVec2::splat(2),
+            -IVec2::splat(self.cell_size as i32) / 2,
         );
     }
 
-    pub fn resolution(&self) -> UVec2 {
-        self.0.resolution()
+    pub fn cell_size(&self) -> u32 {
+        self.cell_size
     }
 
-    pub fn top_left(&self) -> IVec2 {
-        self.0.top_left()
+    pub fn resolution(&self) -> UVec2 {
+        self.metadata.resolution()
     }
 
     pub fn buffer(&self) -> &wgpu::Buffer {
-        self.0.buffer()
+        self.metadata.buffer()
     }
 }
 
 #[derive(Debug)]
 pub struct GridState {
+    resolution: UVec2,
     pos: wgpu::Buffer,
     vel: wgpu::Buffer,
 }
 
 impl GridState {
     pub fn new(device: &wgpu::Device, grid_metadata: &GridMetadata) -> Self {
-        let len = (2 * grid_metadata.resolution().x * grid_metadata.resolution().y) as usize;
+        let resolution = grid_metadata.resolution();
+        let len = (2 * resolution.x * resolution.y) as usize;
         let zeros = vec![Vec2::ZERO; len];
 
         let pos = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Grid State Buffer"),
             contents: bytemuck::cast_slice(&zeros),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
         });
 
         let vel = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Grid Velocity Buffer"),
             contents: bytemuck::cast_slice(&zeros),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
         });
 
-        Self { pos, vel }
+        Self {
+            resolution,
+            pos,
+            vel,
+        }
     }
 
-    pub fn resize(&mut self, device: &wgpu::Device, grid_metadata: &GridMetadata) {
-        let len = (2 * grid_metadata.resolution().x * grid_metadata.resolution().y) as usize;
-        let zeros = vec![Vec2::ZERO; len];
-
-        self.pos = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Grid State Buffer"),
-            contents: bytemuck::cast_slice(&zeros),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+    /// Reallocates the state for the new grid resolution, keeping the simulation of the cells
+    /// present in both the old and the new grid.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        grid_metadata: &GridMetadata,
+    ) {
+        let old = std::mem::replace(self, Self::new(device, grid_metadata));
+
+        let copy_size = old.resolution.min(self.resolution);
+        if copy_size.cmpeq(UVec2::ZERO).any() {
+            return;
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Grid State Resize Command Encoder"),
         });
 
-        self.vel = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Grid Velocity Buffer"),
-            contents: bytemuck::cast_slice(&zeros),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        });
+        const CELL_BYTES: wgpu::BufferAddress = std::mem::size_of::<Vec2>() as wgpu::BufferAddress;
+
+        // Inner and outer points are stored one grid after another, each in row-major order.
+        for (old_buffer, new_buffer) in [(&old.pos, &self.pos), (&old.vel, &self.vel)] {
+            for half in 0..2 {
+                for y in 0..copy_size.y {
+                    let old_index =
+                        half * old.resolution.x * old.resolution.y + y * old.resolution.x;
+                    let new_index =
+                        half * self.resolution.x * self.resolution.y + y * self.resolution.x;
+
+                    encoder.copy_buffer_to_buffer(
+                        old_buffer,
+                        old_index as wgpu::BufferAddress * CELL_BYTES,
+                        new_buffer,
+                        new_index as wgpu::BufferAddress * CELL_BYTES,
+                        copy_size.x as wgpu::BufferAddress * CELL_BYTES,
+                    );
+                }
+            }
+        }
+
+        queue.submit(Some(encoder.finish()));
     }
 
     pub fn pos_buffer(&self) -> &wgpu::Buffer {
@@ -93,3 +138,40 @@ impl GridState {
         &self.vel
     }
 }
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GridSpringPod {
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+/// Spring parameters of the grid points, which can be changed while running.
+#[derive(Debug)]
+pub struct GridSpring {
+    buffer: wgpu::Buffer,
+}
+
+impl GridSpring {
+    pub fn new(device: &wgpu::Device, stiffness: f32, damping: f32) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Grid Spring Buffer"),
+            contents: bytemuck::bytes_of(&GridSpringPod { stiffness, damping }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn update(&self, queue: &wgpu::Queue, stiffness: f32, damping: f32) {
+        queue.write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::bytes_of(&GridSpringPod { stiffness, damping }),
+        );
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+}
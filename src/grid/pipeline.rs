@@ -3,10 +3,22 @@ use glam::*;
 use crate::{
     delta_time::{self, DeltaTime},
     frame::FrameMetadata,
-    grid::{GRID_CELL_SIZE, GridMetadata, GridState, target::Target},
+    grid::{GridMetadata, GridSpring, GridState, target::Target},
+    impulse::{IMPULSE_SPEED, Impulses},
+    meta_shape::MetaShapes,
     mouse::Mouse,
+    pipeline::RADIUS,
 };
 
+/// The buffers shared with the rest of the background that the grid simulation reads.
+#[derive(Debug, Clone, Copy)]
+pub struct GridInputs<'a> {
+    pub frame_metadata: &'a FrameMetadata,
+    pub delta_time: &'a DeltaTime,
+    pub meta_shapes: &'a MetaShapes,
+    pub impulses: &'a Impulses,
+}
+
 #[derive(Debug)]
 pub struct GridProcessor {
     workgroup_size: u32,
@@ -20,12 +32,19 @@ pub struct GridProcessor {
 impl GridProcessor {
     pub fn new(
         device: &wgpu::Device,
-        frame_metadata: &FrameMetadata,
+        inputs: GridInputs,
         grid_metadata: &GridMetadata,
-        delta_time: &DeltaTime,
         grid_state: &GridState,
+        grid_spring: &GridSpring,
         mouse: Vec2,
     ) -> Self {
+        let GridInputs {
+            frame_metadata,
+            delta_time,
+            meta_shapes,
+            impulses,
+        } = inputs;
+
         let workgroup_size = {
             let mut max_size = None;
 
@@ -115,6 +134,46 @@ impl GridProcessor {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 7,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 8,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 9,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -146,6 +205,22 @@ impl GridProcessor {
                     binding: 5,
                     resource: grid_state.vel_buffer().as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: grid_spring.buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: meta_shapes.balls_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 8,
+                    resource: meta_shapes.boxes_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 9,
+                    resource: impulses.buffer().as_entire_binding(),
+                },
             ],
         });
 
@@ -162,8 +237,10 @@ impl GridProcessor {
             entry_point: Some("main"),
             compilation_options: wgpu::PipelineCompilationOptions {
                 constants: &[
-                    ("cell_size", GRID_CELL_SIZE as f64),
+                    ("cell_size", grid_metadata.cell_size() as f64),
                     ("workgroup_size", workgroup_size as f64),
+                    ("base_radius", RADIUS),
+                    ("impulse_speed", IMPULSE_SPEED as f64),
                 ],
                 ..Default::default()
             },
@@ -182,11 +259,18 @@ impl GridProcessor {
     pub fn recreate_bind_group(
         &mut self,
         device: &wgpu::Device,
-        frame_metadata: &FrameMetadata,
+        inputs: GridInputs,
         grid_metadata: &GridMetadata,
-        delta_time: &DeltaTime,
         grid_state: &GridState,
+        grid_spring: &GridSpring,
     ) {
+        let GridInputs {
+            frame_metadata,
+            delta_time,
+            meta_shapes,
+            impulses,
+        } = inputs;
+
         self.bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Grid Processor Bind Group"),
             layout: &self.bind_group_layout,
@@ -215,6 +299,22 @@ impl GridProcessor {
                     binding: 5,
                     resource: grid_state.vel_buffer().as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: grid_spring.buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: meta_shapes.balls_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 8,
+                    resource: meta_shapes.boxes_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 9,
+                    resource: impulses.buffer().as_entire_binding(),
+                },
             ],
         });
     }
@@ -334,7 +434,7 @@ impl GridRenderer {
             });
 
         let compilation_options = wgpu::PipelineCompilationOptions {
-            constants: &[("cell_size", GRID_CELL_SIZE as f64)],
+            constants: &[("cell_size", grid_metadata.cell_size() as f64)],
             ..Default::default()
         };
 
@@ -0,0 +1,158 @@
+use glam::*;
+use wgpu::util::DeviceExt as _;
+
+use crate::meta_shape::{MetaBall, MetaBox};
+
+pub const MAX_IMPULSES: usize = 8;
+
+/// Speed of the impulse wavefront in pixels per second.
+pub const IMPULSE_SPEED: f32 = 900.0;
+
+/// Below this fraction of its strength an impulse is removed.
+const IMPULSE_CUTOFF: f32 = 0.01;
+
+/// Metaballs are displaced as if held by a spring of this stiffness.
+const BALL_STIFFNESS: f32 = 200.0;
+
+/// Panels are heavier than balls, so they are held by a stiffer spring.
+const BOX_STIFFNESS: f32 = 400.0;
+
+/// A one-off shockwave, expanding from `position` at [`IMPULSE_SPEED`].
+///
+/// The wavefront is `radius` pixels wide and its amplitude is `strength * exp(-decay * age)`.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Impulse {
+    pub position: Vec2,
+    pub strength: f32,
+    pub radius: f32,
+    pub decay: f32,
+    pub age: f32,
+    pub _padding: Vec2,
+}
+
+impl Impulse {
+    pub fn new(position: Vec2, strength: f32, radius: f32, decay: f32) -> Self {
+        Self {
+            position,
+            strength,
+            radius,
+            decay,
+            ..Default::default()
+        }
+    }
+
+    /// The shockwave of a click.
+    pub fn ripple(position: Vec2) -> Self {
+        Self::new(position, 4000.0, 48.0, 2.5)
+    }
+
+    pub fn envelope(&self) -> f32 {
+        (-self.decay * self.age).exp()
+    }
+
+    pub fn amplitude(&self) -> f32 {
+        self.strength * self.envelope()
+    }
+
+    pub fn front(&self) -> f32 {
+        self.age * IMPULSE_SPEED
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.envelope() >= IMPULSE_CUTOFF
+    }
+
+    /// Outward acceleration at `pos`, only non-zero around the wavefront.
+    pub fn acceleration(&self, pos: Vec2) -> Vec2 {
+        let disp = pos - self.position;
+        let dist = disp.length();
+        if dist < 1e-3 || self.radius <= 0.0 {
+            return Vec2::ZERO;
+        }
+
+        let t = ((dist - self.front()).abs() / self.radius).clamp(0.0, 1.0);
+        let band = 1.0 - t * t * (3.0 - 2.0 * t);
+
+        disp / dist * self.amplitude() * band
+    }
+}
+
+#[derive(Debug)]
+pub struct Impulses {
+    impulses: Vec<Impulse>,
+    buffer: wgpu::Buffer,
+}
+
+impl Impulses {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Impulses Buffer"),
+            contents: bytemuck::cast_slice(&[Impulse::default(); MAX_IMPULSES]),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            impulses: Vec::with_capacity(MAX_IMPULSES),
+            buffer,
+        }
+    }
+
+    /// Adds an impulse, replacing the oldest one when full.
+    pub fn push(&mut self, impulse: Impulse) {
+        if self.impulses.len() >= MAX_IMPULSES {
+            self.impulses.remove(0);
+        }
+
+        self.impulses.push(impulse);
+    }
+
+    pub fn update(&mut self, queue: &wgpu::Queue, delta_time: f32) {
+        for impulse in self.impulses.iter_mut() {
+            impulse.age += delta_time;
+        }
+
+        self.impulses.retain(Impulse::is_alive);
+
+        // Unused slots have zero strength, so consumers can always loop over all of them.
+        let mut pod = [Impulse::default(); MAX_IMPULSES];
+        pod[..self.impulses.len()].copy_from_slice(&self.impulses);
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&pod));
+    }
+
+    /// Pushes the visible balls and the boxes along with the passing wavefronts.
+    ///
+    /// Boxes move as a whole, pushed by the wavefront at their point closest to each impulse.
+    pub fn displace(&self, balls: &mut [MetaBall], boxes: &mut [MetaBox]) {
+        for ball in balls.iter_mut().filter(|ball| ball.hidden == 0) {
+            let acceleration = self
+                .impulses
+                .iter()
+                .map(|impulse| impulse.acceleration(ball.center))
+                .sum::<Vec2>();
+
+            ball.center += acceleration / BALL_STIFFNESS;
+        }
+
+        for meta_box in boxes.iter_mut() {
+            let acceleration = self
+                .impulses
+                .iter()
+                .map(|impulse| {
+                    impulse.acceleration(impulse.position.clamp(meta_box.min, meta_box.max))
+                })
+                .sum::<Vec2>();
+
+            meta_box.min += acceleration / BOX_STIFFNESS;
+            meta_box.max += acceleration / BOX_STIFFNESS;
+        }
+    }
+
+    pub fn impulses(&self) -> &[Impulse] {
+        &self.impulses
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+}
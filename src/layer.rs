@@ -0,0 +1,435 @@
+use std::sync::mpsc;
+
+use glam::*;
+use vello_svg::vello;
+use wasm_bindgen::{JsCast as _, UnwrapThrowExt as _};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    controller::LayerController,
+    ext::{NodeListExt as _, WindowExt as _},
+    frame::FrameMetadata,
+    pipeline::{
+        BackgroundImageRenderer, BackgroundSvgRenderer, BackgroundVideoRenderer, DecodedImage,
+        SvgElement,
+    },
+    texture_blitter::{TextureBlend, TextureBlit, TextureFit},
+};
+
+/// The layer stack, relative to the page.
+const LAYERS_URL: &str = "layers.json";
+
+/// Elements with this attribute get a layer of the SVG it names, stretched over the element.
+const SVG_LAYER_ATTRIBUTE: &str = "data-bg-svg";
+
+/// The `z` of an [`SVG_LAYER_ATTRIBUTE`] layer, `0` if missing.
+const SVG_LAYER_Z_ATTRIBUTE: &str = "data-bg-z";
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum BackgroundLayerSource {
+    /// A still image, or an animated WebP played back with its frame timing.
+    ///
+    /// The placeholder is shown while the full image, or the variant that best fits the viewport,
+    /// loads in the background.
+    Image {
+        url: String,
+        #[serde(default)]
+        placeholder: Option<String>,
+        #[serde(default)]
+        variants: Vec<ImageVariant>,
+        /// Full size of the image, so the layout does not change as the placeholder is swapped.
+        #[serde(default)]
+        size: Option<[u32; 2]>,
+    },
+    /// A muted looping video.
+    Video { url: String },
+    /// Rasterised at its size on screen.
+    Svg { url: String },
+}
+
+/// A downscaled copy of an image.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ImageVariant {
+    pub url: String,
+    pub width: u32,
+}
+
+/// The smallest variant covering the viewport in device pixels, or the full image if none does.
+fn pick_image_url<'a>(url: &'a str, variants: &'a [ImageVariant]) -> &'a str {
+    let window = web_sys::window().expect_throw("window");
+    let target_width = (window.size().x.max(0) as f64 * window.device_pixel_ratio()).ceil() as u32;
+
+    variants
+        .iter()
+        .filter(|variant| variant.width >= target_width)
+        .min_by_key(|variant| variant.width)
+        .map_or(url, |variant| &variant.url)
+}
+
+/// How a layer is placed relative to its anchor element.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackgroundLayerFit {
+    /// Scaled to the viewport width, but not below half size, and centered on the anchor's top
+    /// edge.
+    #[default]
+    Width,
+    /// The rest are fitted into the anchor's rect, see [`TextureFit`].
+    Stretch,
+    Cover,
+    Contain,
+    Tile,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BackgroundLayerConfig {
+    pub source: BackgroundLayerSource,
+    /// Id of the element the layer follows.
+    pub anchor: String,
+    /// Fraction of the page scroll the layer lags behind by, `0` moves with the anchor.
+    #[serde(default)]
+    pub parallax: f32,
+    #[serde(default)]
+    pub fit: BackgroundLayerFit,
+    /// Layers are drawn from the lowest to the highest.
+    #[serde(default)]
+    pub z: i32,
+    /// Clockwise rotation around the layer center, in degrees.
+    #[serde(default)]
+    pub rotation: f32,
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    #[serde(default)]
+    pub blend: TextureBlend,
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+enum BackgroundLayerRenderer {
+    Image(BackgroundImageRenderer),
+    Video(BackgroundVideoRenderer),
+    Svg(BackgroundSvgRenderer),
+}
+
+struct BackgroundLayer {
+    config: BackgroundLayerConfig,
+    controller: LayerController,
+    /// `None` until an image without a placeholder is loaded.
+    renderer: Option<BackgroundLayerRenderer>,
+    /// The full image being fetched and decoded, swapped in once it arrives.
+    pending_image: Option<mpsc::Receiver<DecodedImage>>,
+}
+
+impl BackgroundLayer {
+    async fn load(
+        config: BackgroundLayerConfig,
+        element: web_sys::HtmlElement,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let controller = LayerController::new(element, config.parallax);
+
+        let mut pending_image = None;
+        let renderer = match &config.source {
+            BackgroundLayerSource::Image {
+                url,
+                placeholder,
+                variants,
+                ..
+            } => {
+                let url = pick_image_url(url, variants).to_owned();
+                let (tx, rx) = mpsc::channel();
+                pending_image = Some(rx);
+
+                // Dropping the sender on failure keeps the placeholder.
+                wasm_bindgen_futures::spawn_local(async move {
+                    match BackgroundImageRenderer::load(&url).await {
+                        Ok(image) => {
+                            let _ = tx.send(image);
+                        }
+                        Err(e) => log::error!("Failed to load background image {url}: {e:?}"),
+                    }
+                });
+
+                match placeholder {
+                    Some(placeholder) => match BackgroundImageRenderer::load(placeholder).await {
+                        Ok(image) => Some(BackgroundLayerRenderer::Image(
+                            BackgroundImageRenderer::new(device, queue, texture_format, image),
+                        )),
+                        Err(e) => {
+                            log::error!("Failed to load placeholder {placeholder}: {e:?}");
+                            None
+                        }
+                    },
+                    None => None,
+                }
+            }
+            BackgroundLayerSource::Video { url } => Some(BackgroundLayerRenderer::Video(
+                BackgroundVideoRenderer::new(device, texture_format, url),
+            )),
+            BackgroundLayerSource::Svg { url } => Some(BackgroundLayerRenderer::Svg(
+                BackgroundSvgRenderer::fetch(device, texture_format, url).await,
+            )),
+        };
+
+        Self {
+            config,
+            controller,
+            renderer,
+            pending_image,
+        }
+    }
+
+    /// Swaps in the full image once it has been fetched.
+    fn receive_image(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_format: wgpu::TextureFormat,
+    ) {
+        let Some(pending_image) = &self.pending_image else {
+            return;
+        };
+
+        match pending_image.try_recv() {
+            Ok(image) => {
+                self.renderer = Some(BackgroundLayerRenderer::Image(
+                    BackgroundImageRenderer::new(device, queue, texture_format, image),
+                ));
+                self.pending_image = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.pending_image = None,
+        }
+    }
+
+    fn blit(&self, frame_metadata: &FrameMetadata) -> Option<TextureBlit> {
+        let renderer = self.renderer.as_ref()?;
+
+        let top_left = self.controller.top_left();
+        let bottom_right = self.controller.bottom_right();
+
+        let mut blit = match self.config.fit {
+            BackgroundLayerFit::Width => {
+                let size = match (&self.config.source, renderer) {
+                    (
+                        BackgroundLayerSource::Image {
+                            size: Some(size), ..
+                        },
+                        _,
+                    ) => UVec2::from(*size),
+                    (_, BackgroundLayerRenderer::Image(renderer)) => renderer.size(),
+                    (_, BackgroundLayerRenderer::Video(renderer)) => renderer.size(),
+                    (_, BackgroundLayerRenderer::Svg(renderer)) => renderer.size(),
+                }
+                .as_vec2();
+                let resolution = frame_metadata.resolution().as_vec2();
+
+                let target_size = size * (resolution.x / size.x).max(0.5);
+                let target_top_left = Vec2::new(
+                    (resolution.x - target_size.x) * 0.5,
+                    top_left.y - target_size.y * 0.5,
+                );
+
+                TextureBlit::new(target_top_left, target_size)
+            }
+            fit => TextureBlit {
+                fit: match fit {
+                    BackgroundLayerFit::Cover => TextureFit::Cover,
+                    BackgroundLayerFit::Contain => TextureFit::Contain,
+                    BackgroundLayerFit::Tile => TextureFit::Tile,
+                    _ => TextureFit::Stretch,
+                },
+                ..TextureBlit::new(top_left, bottom_right - top_left)
+            },
+        };
+
+        blit.rotation = self.config.rotation.to_radians();
+        blit.opacity = self.config.opacity;
+        blit.blend = self.config.blend;
+        Some(blit)
+    }
+}
+
+/// Decorative layers behind the page, described by [`LAYERS_URL`] and [`SVG_LAYER_ATTRIBUTE`].
+pub struct BackgroundLayers {
+    layers: Vec<BackgroundLayer>,
+    texture_format: wgpu::TextureFormat,
+}
+
+impl BackgroundLayers {
+    /// Loads every layer, a missing or invalid [`LAYERS_URL`] is logged and leaves only the SVG
+    /// layers.
+    pub async fn load(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let window = web_sys::window().expect_throw("window");
+
+        let configs = match Self::fetch_configs(&window).await {
+            Ok(configs) => configs,
+            Err(e) => {
+                log::error!("Failed to load background layers: {e:?}");
+                Vec::new()
+            }
+        };
+
+        let document = window.document().expect_throw("document");
+
+        let mut layers = Vec::with_capacity(configs.len());
+        for config in configs {
+            let Some(element) = document
+                .get_element_by_id(&config.anchor)
+                .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok())
+            else {
+                log::error!("Background layer anchor #{} not found", config.anchor);
+                continue;
+            };
+
+            layers
+                .push(BackgroundLayer::load(config, element, device, queue, texture_format).await);
+        }
+
+        let svg_elements = document
+            .query_selector_all(&format!("[{SVG_LAYER_ATTRIBUTE}]"))
+            .expect_throw("svg layer elements");
+        for element in svg_elements
+            .iter()
+            .filter_map(|node| node.dyn_into::<web_sys::HtmlElement>().ok())
+        {
+            let Some(url) = element.get_attribute(SVG_LAYER_ATTRIBUTE) else {
+                continue;
+            };
+
+            let z = match element.get_attribute(SVG_LAYER_Z_ATTRIBUTE) {
+                Some(z) => z.trim().parse().unwrap_or_else(|e| {
+                    log::error!(
+                        "Invalid {SVG_LAYER_Z_ATTRIBUTE} {z:?} on #{}: {e}",
+                        element.id()
+                    );
+                    0
+                }),
+                None => 0,
+            };
+
+            let config = BackgroundLayerConfig {
+                source: BackgroundLayerSource::Svg { url },
+                anchor: element.id(),
+                parallax: 0.0,
+                fit: BackgroundLayerFit::Stretch,
+                z,
+                rotation: 0.0,
+                opacity: 1.0,
+                blend: TextureBlend::default(),
+            };
+
+            layers
+                .push(BackgroundLayer::load(config, element, device, queue, texture_format).await);
+        }
+
+        layers.sort_by_key(|layer| layer.config.z);
+
+        Self {
+            layers,
+            texture_format,
+        }
+    }
+
+    /// Fetches and parses [`LAYERS_URL`].
+    async fn fetch_configs(
+        window: &web_sys::Window,
+    ) -> Result<Vec<BackgroundLayerConfig>, wasm_bindgen::JsValue> {
+        let response = JsFuture::from(window.fetch_with_str(LAYERS_URL))
+            .await?
+            .dyn_into::<web_sys::Response>()?;
+        if !response.ok() {
+            return Err(format!("{LAYERS_URL}: {}", response.status()).into());
+        }
+
+        let json = JsFuture::from(response.json()?).await?;
+        Ok(serde_wasm_bindgen::from_value(json)?)
+    }
+
+    /// The elements of the SVG layer following the element with this id.
+    pub fn svg_elements(&self, anchor: &str) -> Option<&[SvgElement]> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.config.anchor == anchor)
+            .find_map(|layer| match &layer.renderer {
+                Some(BackgroundLayerRenderer::Svg(renderer)) => Some(renderer.elements()),
+                _ => None,
+            })
+    }
+
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        frame_metadata: &FrameMetadata,
+        delta_time: f32,
+    ) {
+        for layer in self.layers.iter_mut() {
+            layer.controller.update(frame_metadata);
+            layer.receive_image(device, queue, self.texture_format);
+
+            match &mut layer.renderer {
+                Some(BackgroundLayerRenderer::Image(renderer)) => {
+                    renderer.update(device, queue, delta_time)
+                }
+                Some(BackgroundLayerRenderer::Video(renderer)) => renderer.update(device, queue),
+                Some(BackgroundLayerRenderer::Svg(..)) | None => {}
+            }
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        vello_renderer: &mut vello::Renderer,
+        background_view: &wgpu::TextureView,
+        frame_metadata: &FrameMetadata,
+    ) {
+        for layer in self.layers.iter_mut() {
+            let Some(blit) = layer.blit(frame_metadata) else {
+                continue;
+            };
+
+            match layer.renderer.as_mut().expect_throw("layer renderer") {
+                BackgroundLayerRenderer::Image(renderer) => renderer.render(
+                    device,
+                    queue,
+                    encoder,
+                    background_view,
+                    frame_metadata,
+                    &blit,
+                ),
+                BackgroundLayerRenderer::Video(renderer) => renderer.render(
+                    device,
+                    queue,
+                    encoder,
+                    background_view,
+                    frame_metadata,
+                    &blit,
+                ),
+                BackgroundLayerRenderer::Svg(renderer) => {
+                    renderer.prepare(
+                        device,
+                        queue,
+                        encoder,
+                        vello_renderer,
+                        frame_metadata,
+                        &blit,
+                    );
+                    renderer.render(encoder, background_view);
+                }
+            }
+        }
+    }
+}
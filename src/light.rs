@@ -0,0 +1,93 @@
+use glam::*;
+
+use crate::{config::LightConfig, frame::FrameMetadata, mouse::Mouse};
+
+pub const LIGHT_HEIGHT: f32 = 600.0;
+pub const LIGHT_INTENSITY: f32 = 0.9;
+const LIGHT_STIFFNESS: f32 = 60.0;
+const LIGHT_DAMPING: f32 = 12.0;
+
+/// Point light over the frame, matching `LiquidLight` in the liquid shaders.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LiquidLight {
+    /// Position in frame pixels.
+    pub position: Vec2,
+    /// Distance from the page towards the viewer, in pixels.
+    pub height: f32,
+    pub intensity: f32,
+    pub color: Vec3,
+    pub specular_power: f32,
+}
+
+impl Default for LiquidLight {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            height: LIGHT_HEIGHT,
+            intensity: LIGHT_INTENSITY,
+            color: Vec3::ONE,
+            specular_power: 64.0,
+        }
+    }
+}
+
+/// The light the liquid is shaded with, trailing the mouse on a spring unless pinned.
+#[derive(Debug)]
+pub struct Light {
+    /// Pinned position in viewport pixels.
+    pinned: Option<Vec2>,
+    /// Position in page pixels.
+    position: Vec2,
+    velocity: Vec2,
+    height: f32,
+    intensity: f32,
+    color: Vec3,
+}
+
+impl Light {
+    pub fn new(config: &LightConfig, position: Vec2) -> Self {
+        Self {
+            pinned: config.position,
+            position,
+            velocity: Vec2::ZERO,
+            height: config.height,
+            intensity: config.intensity,
+            color: config.color,
+        }
+    }
+
+    pub fn update(&mut self, frame_metadata: &FrameMetadata, mouse: &Mouse, delta_time: f32) {
+        let target = match self.pinned {
+            Some(pinned) => pinned + frame_metadata.top_left().as_vec2(),
+            None => mouse.position(),
+        };
+
+        let displacement = target - self.position;
+        let accel = LIGHT_STIFFNESS * displacement - LIGHT_DAMPING * self.velocity;
+        self.velocity += accel * delta_time;
+        self.position += self.velocity * delta_time;
+        if self.velocity.length_squared() < 1e-6 && displacement.length_squared() < 1e-6 {
+            self.position = target;
+            self.velocity = Vec2::ZERO;
+        }
+    }
+
+    pub fn set_pinned(&mut self, pinned: Option<Vec2>) {
+        self.pinned = pinned;
+    }
+
+    pub fn set_color(&mut self, color: Vec3) {
+        self.color = color;
+    }
+
+    pub fn uniform(&self, frame_metadata: &FrameMetadata) -> LiquidLight {
+        LiquidLight {
+            position: self.position - frame_metadata.top_left().as_vec2(),
+            height: self.height,
+            intensity: self.intensity,
+            color: self.color,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use glam::*;
+
+/// How long switching between materials takes, in seconds.
+const TWEEN_DURATION: f32 = 0.6;
+
+/// Optical properties of the liquid glass, matching `LiquidMaterial` in the liquid shaders.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LiquidMaterial {
+    /// Colour the light fades to inside the glass.
+    pub frost_rgb: Vec3,
+    /// How much of the frost colour is mixed over the result.
+    pub frost_strength: f32,
+    pub refractive_index: f32,
+    /// Thickness of the glass in pixels, on top of the field shape.
+    pub quad_height: f32,
+    /// Extra thickness per pixel of panel elevation.
+    pub elevation_scale: f32,
+    /// How fast light fades to the frost colour per pixel of thickness.
+    pub frost_absorption: f32,
+    /// Refractive index spread between the red and blue channels, only used with dispersion.
+    pub dispersion: f32,
+    /// Brightness of the light focused through the glass, only used with dispersion.
+    pub caustic_strength: f32,
+    pub padding: Vec2,
+}
+
+impl LiquidMaterial {
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+
+        Self {
+            frost_rgb: self.frost_rgb.lerp(other.frost_rgb, t),
+            frost_strength: mix(self.frost_strength, other.frost_strength),
+            refractive_index: mix(self.refractive_index, other.refractive_index),
+            quad_height: mix(self.quad_height, other.quad_height),
+            elevation_scale: mix(self.elevation_scale, other.elevation_scale),
+            frost_absorption: mix(self.frost_absorption, other.frost_absorption),
+            dispersion: mix(self.dispersion, other.dispersion),
+            caustic_strength: mix(self.caustic_strength, other.caustic_strength),
+            padding: Vec2::ZERO,
+        }
+    }
+}
+
+impl Default for LiquidMaterial {
+    fn default() -> Self {
+        LiquidMaterialPreset::default().material()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum LiquidMaterialPreset {
+    ClearGlass,
+    #[default]
+    Frosted,
+    Tinted,
+    Water,
+}
+
+impl LiquidMaterialPreset {
+    pub fn material(self) -> LiquidMaterial {
+        match self {
+            Self::ClearGlass => LiquidMaterial {
+                frost_rgb: Vec3::splat(0.05),
+                frost_strength: 0.0,
+                refractive_index: 1.5,
+                quad_height: 18.0,
+                elevation_scale: 18.0,
+                frost_absorption: 0.002,
+                dispersion: 0.04,
+                caustic_strength: 0.6,
+                padding: Vec2::ZERO,
+            },
+            Self::Frosted => LiquidMaterial {
+                frost_rgb: Vec3::splat(0.05),
+                frost_strength: 0.1,
+                refractive_index: 1.77,
+                quad_height: 18.0,
+                elevation_scale: 18.0,
+                frost_absorption: 0.01,
+                dispersion: 0.02,
+                caustic_strength: 0.3,
+                padding: Vec2::ZERO,
+            },
+            Self::Tinted => LiquidMaterial {
+                frost_rgb: Vec3::new(0.05, 0.09, 0.16),
+                frost_strength: 0.25,
+                refractive_index: 1.6,
+                quad_height: 18.0,
+                elevation_scale: 18.0,
+                frost_absorption: 0.02,
+                dispersion: 0.03,
+                caustic_strength: 0.4,
+                padding: Vec2::ZERO,
+            },
+            Self::Water => LiquidMaterial {
+                frost_rgb: Vec3::new(0.02, 0.08, 0.1),
+                frost_strength: 0.04,
+                refractive_index: 1.33,
+                quad_height: 12.0,
+                elevation_scale: 12.0,
+                frost_absorption: 0.015,
+                dispersion: 0.015,
+                caustic_strength: 0.8,
+                padding: Vec2::ZERO,
+            },
+        }
+    }
+}
+
+/// Eases the liquid from one material to another.
+#[derive(Debug)]
+pub struct LiquidMaterialTween {
+    from: LiquidMaterial,
+    to: LiquidMaterial,
+    elapsed: f32,
+}
+
+impl LiquidMaterialTween {
+    pub fn new(material: LiquidMaterial) -> Self {
+        Self {
+            from: material,
+            to: material,
+            elapsed: TWEEN_DURATION,
+        }
+    }
+
+    /// Starts easing towards `material` from wherever the current tween is.
+    pub fn start(&mut self, material: LiquidMaterial) {
+        self.from = self.current();
+        self.to = material;
+        self.elapsed = 0.0;
+    }
+
+    /// Advances the tween, returns the new material if it changed.
+    pub fn update(&mut self, delta_time: f32) -> Option<LiquidMaterial> {
+        if !self.is_animating() {
+            return None;
+        }
+
+        self.elapsed = (self.elapsed + delta_time).min(TWEEN_DURATION);
+        Some(self.current())
+    }
+
+    pub fn current(&self) -> LiquidMaterial {
+        let t = (self.elapsed / TWEEN_DURATION).clamp(0.0, 1.0);
+        self.from.lerp(&self.to, t * t * (3.0 - 2.0 * t))
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.elapsed < TWEEN_DURATION
+    }
+}
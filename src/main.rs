@@ -1,15 +1,24 @@
 use std::{cell::OnceCell, rc::Rc, sync::mpsc};
 
 use wasm_bindgen::prelude::*;
+use web_sys::js_sys;
 
 use crate::{
     background::{Background, BackgroundEvent},
+    config::BackgroundConfig,
     ext::MouseEventExt as _,
     gpu::Gpu,
+    liquid_material::LiquidMaterialPreset,
+    post_effect::{
+        PostEffectKind, PostEffectParams,
+        effect::{BloomParams, ChromaticAberrationParams, VignetteParams},
+    },
     theme::Theme,
 };
 
 mod background;
+mod blur;
+mod config;
 mod controller;
 mod delta_time;
 mod event_listeners;
@@ -17,12 +26,21 @@ mod ext;
 mod frame;
 mod gpu;
 mod grid;
+mod impulse;
+mod layer;
+mod light;
+mod liquid_material;
 mod logger;
 mod mar_sq;
 mod meta_field;
 mod meta_shape;
+mod mipmap;
 mod mouse;
 mod pipeline;
+mod post_effect;
+#[cfg(test)]
+mod readback;
+mod svg_export;
 mod texture_blitter;
 mod theme;
 
@@ -37,6 +55,153 @@ macro_rules! add_event_listener {
     };
 }
 
+thread_local! {
+    static BACKGROUND_EVENTS: OnceCell<mpsc::Sender<BackgroundEvent>> = const { OnceCell::new() };
+}
+
+/// Sends an event to the running background, logging `what` failed to send.
+///
+/// Returns whether the event was sent, it is dropped when the background VFX is not running.
+fn send_event(event: BackgroundEvent, what: &str) -> bool {
+    BACKGROUND_EVENTS.with(|tx| {
+        let Some(tx) = tx.get() else {
+            return false;
+        };
+
+        tx.send(event)
+            .inspect_err(|e| log::error!("Failed to send {what} event: {e}"))
+            .is_ok()
+    })
+}
+
+/// Changes the blur radius behind the liquid, does nothing when the background VFX is not running.
+#[wasm_bindgen(js_name = setBackgroundBlurRadius)]
+pub fn set_background_blur_radius(radius: f32) {
+    send_event(BackgroundEvent::BlurRadius(radius.max(0.0)), "blur radius");
+}
+
+/// Eases the liquid to a material preset, logs an error if the name is unknown.
+#[wasm_bindgen(js_name = setLiquidMaterial)]
+pub fn set_liquid_material(name: &str) {
+    let preset = match name.parse::<LiquidMaterialPreset>() {
+        Ok(preset) => preset,
+        Err(e) => {
+            log::error!("Invalid liquid material {name:?}: {e}");
+            return;
+        }
+    };
+
+    send_event(BackgroundEvent::LiquidMaterial(preset), "liquid material");
+}
+
+/// Pins the light the liquid is shaded with to a viewport position, or makes it follow the mouse
+/// again when either coordinate is missing.
+#[wasm_bindgen(js_name = setLightPosition)]
+pub fn set_light_position(x: Option<f32>, y: Option<f32>) {
+    let position = x.zip(y).map(|(x, y)| glam::Vec2::new(x, y));
+
+    send_event(BackgroundEvent::LightPosition(position), "light position");
+}
+
+/// Changes the colour of the light the liquid is shaded with, each channel from 0 to 1.
+#[wasm_bindgen(js_name = setLightColor)]
+pub fn set_light_color(r: f32, g: f32, b: f32) {
+    let color = glam::Vec3::new(r, g, b).clamp(glam::Vec3::ZERO, glam::Vec3::ONE);
+
+    send_event(BackgroundEvent::LightColor(color), "light color");
+}
+
+/// Changes the spring stiffness and damping of the grid points, does nothing when the grid is not
+/// enabled.
+#[wasm_bindgen(js_name = setGridSpring)]
+pub fn set_grid_spring(stiffness: f32, damping: f32) {
+    let event = BackgroundEvent::GridSpring {
+        stiffness: stiffness.max(0.0),
+        damping: damping.max(0.0),
+    };
+    send_event(event, "grid spring");
+}
+
+/// Turns a post effect of the `post=` chain on or off, logs an error if the name is unknown.
+#[wasm_bindgen(js_name = setPostEffectEnabled)]
+pub fn set_post_effect_enabled(name: &str, enabled: bool) {
+    let kind = match name.parse::<PostEffectKind>() {
+        Ok(kind) => kind,
+        Err(e) => {
+            log::error!("Invalid post effect {name:?}: {e}");
+            return;
+        }
+    };
+
+    send_event(
+        BackgroundEvent::PostEffectEnabled(kind, enabled),
+        "post effect enabled",
+    );
+}
+
+/// Changes the bloom glow, the radius is in pixels.
+#[wasm_bindgen(js_name = setBloomParams)]
+pub fn set_bloom_params(threshold: f32, intensity: f32, radius: f32, full_elevation: f32) {
+    let params = PostEffectParams::Bloom(BloomParams {
+        threshold,
+        intensity: intensity.max(0.0),
+        radius: radius.max(0.0),
+        full_elevation: full_elevation.max(f32::EPSILON),
+    });
+    send_event(BackgroundEvent::PostEffectParams(params), "bloom params");
+}
+
+/// Changes the vignette, the radius is relative to the half diagonal of the viewport.
+#[wasm_bindgen(js_name = setVignetteParams)]
+pub fn set_vignette_params(strength: f32, radius: f32, softness: f32) {
+    let params = PostEffectParams::Vignette(VignetteParams {
+        strength: strength.max(0.0),
+        radius: radius.max(0.0),
+        softness: softness.max(f32::EPSILON),
+        ..Default::default()
+    });
+    send_event(BackgroundEvent::PostEffectParams(params), "vignette params");
+}
+
+/// Changes the strength of the film grain.
+#[wasm_bindgen(js_name = setFilmGrainStrength)]
+pub fn set_film_grain_strength(strength: f32) {
+    let params = PostEffectParams::FilmGrain {
+        strength: strength.max(0.0),
+    };
+    send_event(
+        BackgroundEvent::PostEffectParams(params),
+        "film grain params",
+    );
+}
+
+/// Changes the largest channel offset of the chromatic aberration in pixels.
+#[wasm_bindgen(js_name = setChromaticAberrationStrength)]
+pub fn set_chromatic_aberration_strength(strength: f32) {
+    let params = PostEffectParams::ChromaticAberration(ChromaticAberrationParams {
+        strength: strength.max(0.0),
+        ..Default::default()
+    });
+    send_event(
+        BackgroundEvent::PostEffectParams(params),
+        "chromatic aberration params",
+    );
+}
+
+/// Exports the current liquid contours of the whole page as an SVG document.
+///
+/// The promise resolves on the next frame, or to `undefined` when the background VFX is not
+/// running.
+#[wasm_bindgen(js_name = exportLiquidSvg)]
+pub fn export_liquid_svg() -> js_sys::Promise {
+    js_sys::Promise::new(&mut |resolve, _reject| {
+        let event = BackgroundEvent::ExportLiquidSvg(resolve.clone());
+        if !send_event(event, "liquid SVG export") {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    })
+}
+
 fn main() {
     logger::init(if cfg!(debug_assertions) {
         log::LevelFilter::Debug
@@ -53,6 +218,7 @@ fn main() {
     let search = window.location().search().unwrap_throw();
     let params = web_sys::UrlSearchParams::new_with_str(&search).unwrap_throw();
     let bgvfx_enabled = params.get("bgvfx") == Some("1".to_string());
+    let config = BackgroundConfig::from_params(&params);
 
     Theme::set_current(Theme::Dark);
 
@@ -78,7 +244,10 @@ fn main() {
         let gpu = Gpu::new(canvas.clone()).await;
 
         let (tx, rx) = mpsc::channel();
-        let mut background = Background::new(gpu, canvas.clone(), rx).await;
+        let mut background = Background::new(gpu, canvas.clone(), config, rx).await;
+        BACKGROUND_EVENTS
+            .with(|events| events.set(tx.clone()))
+            .unwrap_throw();
         add_event_listener!(window, "pointermove", {
             let tx = tx.clone();
             move |event: web_sys::PointerEvent| {
@@ -91,6 +260,18 @@ fn main() {
                 }
             }
         }; FnMut(_));
+        add_event_listener!(window, "pointerdown", {
+            let tx = tx.clone();
+            move |event: web_sys::PointerEvent| {
+                if !event.is_primary() {
+                    return;
+                }
+
+                if let Err(e) = tx.send(BackgroundEvent::Ripple(event.client_position())) {
+                    log::error!("Failed to send ripple event: {e}");
+                }
+            }
+        }; FnMut(_));
         add_event_listener!(window.visual_viewport().unwrap_throw(), "resize", {
             let tx = tx.clone();
             move || {
@@ -0,0 +1,275 @@
+use ahash::HashMap;
+use glam::*;
+
+/// A crossing point of the iso-contour, identified by the cell edge it lies on.
+///
+/// `horizontal` edges go from `coord` to `coord + (1, 0)`, vertical ones to `coord + (0, 1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct EdgeKey {
+    coord: IVec2,
+    horizontal: bool,
+}
+
+/// Extracts the iso-contours of `field` at `iso` as closed polylines in field coordinates.
+///
+/// Everything outside of the field is treated as `0.0`, so `iso` must be positive for contours
+/// touching the edge to be closed.
+pub fn contours(field: &[f32], resolution: UVec2, iso: f32) -> Vec<Vec<Vec2>> {
+    let load = |coord: IVec2| {
+        if coord.cmplt(IVec2::ZERO).any() || coord.cmpge(resolution.as_ivec2()).any() {
+            0.0
+        } else {
+            field[(coord.y as u32 * resolution.x + coord.x as u32) as usize]
+        }
+    };
+
+    let mut points = HashMap::<EdgeKey, Vec2>::default();
+    let mut neighbours = HashMap::<EdgeKey, Vec<EdgeKey>>::default();
+
+    let mut point = |key: EdgeKey| {
+        points.entry(key).or_insert_with(|| {
+            let start = key.coord;
+            let end = start + if key.horizontal { IVec2::X } else { IVec2::Y };
+            lerp(start.as_vec2(), end.as_vec2(), load(start), load(end), iso)
+        });
+        key
+    };
+
+    for y in -1..resolution.y as i32 {
+        for x in -1..resolution.x as i32 {
+            let coord = IVec2::new(x, y);
+
+            let patt = ((load(coord) >= iso) as u32) << 3
+                | ((load(coord + IVec2::new(1, 0)) >= iso) as u32) << 2
+                | ((load(coord + IVec2::new(1, 1)) >= iso) as u32) << 1
+                | (load(coord + IVec2::new(0, 1)) >= iso) as u32;
+
+            let top = EdgeKey {
+                coord,
+                horizontal: true,
+            };
+            let bottom = EdgeKey {
+                coord: coord + IVec2::Y,
+                horizontal: true,
+            };
+            let left = EdgeKey {
+                coord,
+                horizontal: false,
+            };
+            let right = EdgeKey {
+                coord: coord + IVec2::X,
+                horizontal: false,
+            };
+
+            // Saddles keep the centre inside, same as `quad_marching_squares.wgsl`.
+            let segments: &[(EdgeKey, EdgeKey)] = match patt {
+                1 | 14 => &[(left, bottom)],
+                2 | 13 => &[(bottom, right)],
+                3 | 12 => &[(left, right)],
+                4 | 11 => &[(top, right)],
+                6 | 9 => &[(top, bottom)],
+                7 | 8 => &[(top, left)],
+                5 => &[(top, left), (bottom, right)],
+                10 => &[(top, right), (left, bottom)],
+                _ => &[],
+            };
+
+            for &(a, b) in segments {
+                let (a, b) = (point(a), point(b));
+                neighbours.entry(a).or_default().push(b);
+                neighbours.entry(b).or_default().push(a);
+            }
+        }
+    }
+
+    let mut contours = Vec::new();
+    let mut visited = ahash::HashSet::<EdgeKey>::default();
+
+    for &start in neighbours.keys() {
+        if !visited.insert(start) {
+            continue;
+        }
+
+        let mut contour = vec![points[&start]];
+        let mut prev = start;
+        let mut curr = neighbours[&start][0];
+
+        while curr != start && visited.insert(curr) {
+            contour.push(points[&curr]);
+
+            let Some(&next) = neighbours[&curr].iter().find(|&&next| next != prev) else {
+                break;
+            };
+
+            prev = curr;
+            curr = next;
+        }
+
+        contours.push(contour);
+    }
+
+    contours
+}
+
+/// Simplifies a closed polyline with the Ramer-Douglas-Peucker algorithm.
+pub fn simplify(contour: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    if contour.len() < 4 {
+        return contour.to_vec();
+    }
+
+    // Split the ring at the point furthest from the start, so both halves are open polylines.
+    let far = (1..contour.len())
+        .max_by(|&a, &b| {
+            let dist_a = contour[a].distance_squared(contour[0]);
+            let dist_b = contour[b].distance_squared(contour[0]);
+            dist_a.total_cmp(&dist_b)
+        })
+        .expect("contour has more than one point");
+
+    let mut ring = contour.to_vec();
+    ring.push(contour[0]);
+
+    let mut simplified = simplify_open(&ring[..=far], epsilon);
+    simplified.pop();
+    simplified.extend(simplify_open(&ring[far..], epsilon));
+    simplified.pop();
+
+    simplified
+}
+
+fn simplify_open(polyline: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    let (first, last) = (polyline[0], polyline[polyline.len() - 1]);
+
+    let furthest = polyline[1..polyline.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| (i + 1, distance_to_segment(p, first, last)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    match furthest {
+        Some((i, dist)) if dist > epsilon => {
+            let mut simplified = simplify_open(&polyline[..=i], epsilon);
+            simplified.pop();
+            simplified.extend(simplify_open(&polyline[i..], epsilon));
+            simplified
+        }
+        _ => vec![first, last],
+    }
+}
+
+fn distance_to_segment(p: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let line = end - start;
+    let len_sq = line.length_squared();
+    if len_sq == 0.0 {
+        return p.distance(start);
+    }
+
+    let t = ((p - start).dot(line) / len_sq).clamp(0.0, 1.0);
+    p.distance(start + t * line)
+}
+
+fn lerp(p1: Vec2, p2: Vec2, v1: f32, v2: f32, iso: f32) -> Vec2 {
+    if v1 == v2 {
+        return (p1 + p2) * 0.5;
+    }
+
+    let t = (iso - v1) / (v2 - v1);
+    p1 + t * (p2 - p1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5x5 field with one sample above the iso value at the centre.
+    fn single_peak() -> Vec<f32> {
+        let mut field = vec![0.0; 25];
+        field[2 * 5 + 2] = 2.0;
+        field
+    }
+
+    #[test]
+    fn single_peak_links_into_one_ring() {
+        let contours = contours(&single_peak(), UVec2::splat(5), 1.0);
+
+        assert_eq!(contours.len(), 1);
+        assert_eq!(contours[0].len(), 4);
+        for p in &contours[0] {
+            assert!((p.distance(Vec2::splat(2.0)) - 0.5).abs() < 1e-5, "{p}");
+        }
+    }
+
+    #[test]
+    fn separate_peaks_link_into_separate_rings() {
+        let mut field = vec![0.0; 7 * 3];
+        field[7 + 1] = 2.0;
+        field[7 + 5] = 2.0;
+
+        let contours = contours(&field, UVec2::new(7, 3), 1.0);
+
+        assert_eq!(contours.len(), 2);
+        assert!(contours.iter().all(|contour| contour.len() == 4));
+    }
+
+    #[test]
+    fn contour_touching_the_edge_is_closed() {
+        let contours = contours(&[2.0; 9], UVec2::splat(3), 1.0);
+
+        assert_eq!(contours.len(), 1);
+        // Three crossings on each side, halfway to the zero outside the field.
+        assert_eq!(contours[0].len(), 12);
+        for p in &contours[0] {
+            assert!(p.cmpge(Vec2::splat(-0.5)).all() && p.cmple(Vec2::splat(2.5)).all());
+        }
+    }
+
+    #[test]
+    fn simplify_removes_collinear_points() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(0.0, 1.0),
+        ];
+
+        let simplified = simplify(&square, 0.1);
+
+        assert_eq!(
+            simplified,
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(0.0, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn simplify_keeps_points_beyond_epsilon() {
+        let contour = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.05),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(2.0, -2.0),
+        ];
+
+        let simplified = simplify(&contour, 0.1);
+
+        assert!(!simplified.contains(&Vec2::new(1.0, 0.05)));
+        assert!(simplified.contains(&Vec2::new(3.0, 1.0)));
+    }
+
+    #[test]
+    fn simplify_keeps_small_contours() {
+        let triangle = [Vec2::ZERO, Vec2::X, Vec2::Y];
+
+        assert_eq!(simplify(&triangle, 10.0), triangle);
+    }
+}
@@ -0,0 +1,123 @@
+use glam::*;
+
+use crate::{
+    meta_field::MetaField,
+    theme::{Theme, ThemePropertyName},
+};
+
+/// Most contour levels a [`ContourLines`] can be configured with.
+pub const MAX_CONTOUR_COUNT: u32 = 32;
+
+/// The meta field channel the contour levels are thresholds on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ContourChannel {
+    #[default]
+    Magnitude,
+    Elevation,
+}
+
+/// Evenly spaced iso levels, `start + i * step` for `i` in `0..count`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourLevels {
+    pub channel: ContourChannel,
+    pub count: u32,
+    pub start: f32,
+    pub step: f32,
+}
+
+impl ContourLevels {
+    /// Spaces `count` levels evenly from `min` to `max`, both inclusive.
+    pub fn evenly_spaced(channel: ContourChannel, min: f32, max: f32, count: u32) -> Self {
+        let step = if count > 1 {
+            (max - min) / (count - 1) as f32
+        } else {
+            0.0
+        };
+
+        Self {
+            channel,
+            count: count.max(1),
+            start: min,
+            step,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ContourLine {
+    pub segment: IVec4,
+    pub level: u32,
+    pub padding: UVec3,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ContourLineStyle {
+    pub low_color: Vec4,
+    pub high_color: Vec4,
+    pub width: f32,
+    pub level_count: u32,
+    pub padding: UVec2,
+}
+
+impl ContourLineStyle {
+    /// Fades from a faint foreground on the lowest level to the accent colour on the highest.
+    pub fn from_theme(levels: &ContourLevels, width: f32) -> Self {
+        let properties = Theme::current().properties();
+        let color = |name: ThemePropertyName| {
+            properties
+                .get(&name)
+                .and_then(|property| property.vec4())
+                .unwrap_or(Vec4::ONE)
+        };
+
+        Self {
+            low_color: color(ThemePropertyName::Foreground).xyz().extend(0.15),
+            high_color: color(ThemePropertyName::Ttd).xyz().extend(0.8),
+            width,
+            level_count: levels.count,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ContourLines {
+    buffer: wgpu::Buffer,
+    levels: ContourLevels,
+}
+
+impl ContourLines {
+    pub fn new(device: &wgpu::Device, meta_field: &MetaField, levels: ContourLevels) -> Self {
+        let resolution = meta_field.resolution().as_u64vec2();
+        let line_size = std::mem::size_of::<ContourLine>() as u64;
+
+        // Every level can cross a cell twice, which can outgrow the binding limit on large screens
+        // with many levels.
+        let max_count = ((resolution.x - 1) * (resolution.y - 1) * 2 * levels.count as u64)
+            .min(device.limits().max_storage_buffer_binding_size as u64 / line_size);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Contour Line Buffer"),
+            size: line_size * max_count,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        Self { buffer, levels }
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, meta_field: &MetaField) {
+        *self = Self::new(device, meta_field, self.levels);
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    pub fn levels(&self) -> &ContourLevels {
+        &self.levels
+    }
+}
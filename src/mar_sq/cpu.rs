@@ -0,0 +1,412 @@
+use glam::*;
+
+use crate::{
+    meta_field::MetaFieldSample,
+    meta_shape::{MetaBall, MetaBox},
+};
+
+/// Four pixel positions of a quad, the same layout as an instance in [`Quads`](super::quad::Quads).
+#[cfg(test)]
+pub type Quad = [IVec2; 4];
+
+/// Samples the meta field at every cell, mirroring `meta_field_process.wgsl`.
+///
+/// Each cell is `(magnitude, elevation)` in row-major order.
+pub fn sample_field(
+    balls: &[MetaBall],
+    boxes: &[MetaBox],
+    resolution: UVec2,
+    cell_size: u32,
+    offset: IVec2,
+    top_left: IVec2,
+) -> Vec<Vec2> {
+    (0..resolution.y)
+        .flat_map(|y| (0..resolution.x).map(move |x| UVec2::new(x, y)))
+        .map(|coord| {
+            let cell_pos = coord.as_ivec2() * cell_size as i32 + offset + top_left;
+            let sample = MetaFieldSample::sample(balls, boxes, cell_pos.as_vec2());
+            Vec2::new(sample.magnitude, sample.elevation)
+        })
+        .collect()
+}
+
+/// Runs marching squares over the field, mirroring `quad_marching_squares.wgsl`.
+///
+/// Out of bounds loads read zero like `textureLoad` does.
+#[cfg(test)]
+pub fn quads(field: &[Vec2], resolution: UVec2, cell_size: u32, offset: IVec2) -> Vec<Quad> {
+    let load = |coord: UVec2| {
+        if coord.cmplt(resolution).all() {
+            field[(coord.y * resolution.x + coord.x) as usize].x
+        } else {
+            0.0
+        }
+    };
+    let to_pixel = |pos: Vec2| (pos * cell_size as f32).as_ivec2() + offset;
+
+    let mut quads = Vec::new();
+    let mut push_quad = |v0: Vec2, v1: Vec2, v2: Vec2, v3: Vec2| {
+        quads.push([to_pixel(v0), to_pixel(v1), to_pixel(v2), to_pixel(v3)]);
+    };
+
+    for y in 0..resolution.y {
+        for x in 0..resolution.x {
+            let top_left_coord = UVec2::new(x, y);
+            let top_right_coord = top_left_coord + UVec2::new(1, 0);
+            let bottom_right_coord = top_left_coord + UVec2::new(1, 1);
+            let bottom_left_coord = top_left_coord + UVec2::new(0, 1);
+
+            let top_left = load(top_left_coord);
+            let top_right = load(top_right_coord);
+            let bottom_right = load(bottom_right_coord);
+            let bottom_left = load(bottom_left_coord);
+
+            let patt = ((top_left >= 1.0) as u32) << 3
+                | ((top_right >= 1.0) as u32) << 2
+                | ((bottom_right >= 1.0) as u32) << 1
+                | (bottom_left >= 1.0) as u32;
+
+            let top_left_pos = top_left_coord.as_vec2();
+            let top_right_pos = top_right_coord.as_vec2();
+            let bottom_right_pos = bottom_right_coord.as_vec2();
+            let bottom_left_pos = bottom_left_coord.as_vec2();
+
+            let top_pos = lerp(top_left_pos, top_right_pos, top_left, top_right);
+            let right_pos = lerp(top_right_pos, bottom_right_pos, top_right, bottom_right);
+            let bottom_pos = lerp(bottom_left_pos, bottom_right_pos, bottom_left, bottom_right);
+            let left_pos = lerp(top_left_pos, bottom_left_pos, top_left, bottom_left);
+
+            match patt {
+                // 1 point
+                1 => push_quad(bottom_pos, left_pos, bottom_left_pos, bottom_left_pos),
+                2 => push_quad(right_pos, bottom_pos, bottom_right_pos, bottom_right_pos),
+                4 => push_quad(top_pos, right_pos, top_right_pos, top_right_pos),
+                8 => push_quad(top_left_pos, left_pos, top_pos, top_pos),
+
+                // 2 points
+                3 => push_quad(right_pos, left_pos, bottom_right_pos, bottom_left_pos),
+                6 => push_quad(top_pos, bottom_pos, top_right_pos, bottom_right_pos),
+                9 => push_quad(top_left_pos, bottom_left_pos, top_pos, bottom_pos),
+                12 => push_quad(top_left_pos, left_pos, top_right_pos, right_pos),
+                5 => {
+                    push_quad(top_pos, bottom_pos, top_right_pos, right_pos);
+                    push_quad(top_pos, left_pos, bottom_pos, bottom_left_pos);
+                }
+                10 => {
+                    push_quad(top_left_pos, bottom_right_pos, top_pos, right_pos);
+                    push_quad(top_left_pos, left_pos, bottom_right_pos, bottom_pos);
+                }
+
+                // 3 points
+                7 => {
+                    push_quad(top_pos, bottom_left_pos, top_right_pos, bottom_right_pos);
+                    push_quad(top_pos, left_pos, bottom_left_pos, bottom_left_pos);
+                }
+                11 => {
+                    push_quad(top_left_pos, bottom_right_pos, top_pos, right_pos);
+                    push_quad(
+                        top_left_pos,
+                        bottom_left_pos,
+                        bottom_right_pos,
+                        bottom_right_pos,
+                    );
+                }
+                13 => {
+                    push_quad(top_left_pos, bottom_pos, top_right_pos, right_pos);
+                    push_quad(top_left_pos, bottom_left_pos, bottom_pos, bottom_pos);
+                }
+                14 => {
+                    push_quad(top_left_pos, bottom_pos, top_right_pos, bottom_right_pos);
+                    push_quad(top_left_pos, left_pos, bottom_pos, bottom_pos);
+                }
+
+                // 4 points
+                15 => push_quad(
+                    top_left_pos,
+                    bottom_left_pos,
+                    top_right_pos,
+                    bottom_right_pos,
+                ),
+
+                _ => {}
+            }
+        }
+    }
+
+    quads
+}
+
+#[cfg(test)]
+fn lerp(p1: Vec2, p2: Vec2, v1: f32, v2: f32) -> Vec2 {
+    if v1 == v2 {
+        return Vec2::splat(-1.0);
+    }
+
+    let t = (1.0 - v1) / (v2 - v1);
+    p1 + t * (p2 - p1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        frame::FrameMetadata,
+        mar_sq::{
+            pipeline::MarchingSquaresProcessor,
+            quad::Quads,
+            traits::MarchingSquaresShape,
+            triangle::{Triangle, Triangles},
+        },
+        meta_field::MetaField,
+        meta_shape::MetaShapes,
+        pipeline::MetaFieldProcessor,
+        readback,
+    };
+
+    const RESOLUTION: UVec2 = UVec2::new(256, 192);
+    const CELL_SIZE: u32 = 8;
+
+    /// Compares two sets of quads regardless of order, allowing each vertex to be off by `tolerance` pixels.
+    ///
+    /// The GPU appends quads with an atomic counter, so their order is not deterministic.
+    fn quads_match(a: &[Quad], b: &[Quad], tolerance: i32) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+
+        let is_close = |a: &Quad, b: &Quad| {
+            a.iter()
+                .zip(b.iter())
+                .all(|(a, b)| (*a - *b).abs().max_element() <= tolerance)
+        };
+
+        let mut unmatched = b.to_vec();
+        a.iter()
+            .all(|a| match unmatched.iter().position(|b| is_close(a, b)) {
+                Some(i) => {
+                    unmatched.swap_remove(i);
+                    true
+                }
+                None => false,
+            })
+    }
+
+    /// The meta field of a fixed set of shapes, evaluated on the GPU.
+    struct Scene {
+        meta_field: MetaField,
+        field: Vec<Vec2>,
+    }
+
+    impl Scene {
+        fn new(
+            device: &wgpu::Device,
+            queue: &wgpu::Queue,
+            balls: &[MetaBall],
+            boxes: &[MetaBox],
+        ) -> Self {
+            let frame_metadata = FrameMetadata::new(device, RESOLUTION, IVec2::ZERO);
+
+            let mut meta_shapes = MetaShapes::new(device, balls.len(), boxes.len());
+            meta_shapes.balls_mut().copy_from_slice(balls);
+            meta_shapes.boxes_mut().copy_from_slice(boxes);
+            meta_shapes.ensure_buffer(queue);
+
+            let meta_field = MetaField::new(device, &frame_metadata, CELL_SIZE);
+            let meta_field_processor =
+                MetaFieldProcessor::new(device, &frame_metadata, &meta_shapes, &meta_field);
+
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Meta Field Test Command Encoder"),
+            });
+            meta_field_processor.process(&mut encoder, meta_field.resolution());
+            queue.submit(Some(encoder.finish()));
+
+            let field = sample_field(
+                balls,
+                boxes,
+                meta_field.resolution(),
+                CELL_SIZE,
+                meta_field.offset(),
+                frame_metadata.top_left(),
+            );
+
+            Self { meta_field, field }
+        }
+
+        fn cpu_quads(&self) -> Vec<Quad> {
+            quads(
+                &self.field,
+                self.meta_field.resolution(),
+                CELL_SIZE,
+                self.meta_field.offset(),
+            )
+        }
+
+        fn gpu_quads(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Vec<Quad> {
+            let quads = Quads::new(device, &self.meta_field);
+            let processor =
+                MarchingSquaresProcessor::<Quads>::new(device, &self.meta_field, &quads);
+            self.run(device, queue, &processor);
+
+            readback::block_on(quads.read_back(device, queue, processor.indirect_buffer()))
+                .expect("read back quads")
+        }
+
+        fn gpu_triangles(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Vec<Triangle> {
+            let triangles = Triangles::new(device, &self.meta_field);
+            let processor =
+                MarchingSquaresProcessor::<Triangles>::new(device, &self.meta_field, &triangles);
+            self.run(device, queue, &processor);
+
+            readback::block_on(triangles.read_back(device, queue, processor.indirect_buffer()))
+                .expect("read back triangles")
+        }
+
+        fn run<Shape: MarchingSquaresShape>(
+            &self,
+            device: &wgpu::Device,
+            queue: &wgpu::Queue,
+            processor: &MarchingSquaresProcessor<Shape>,
+        ) {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Marching Squares Test Command Encoder"),
+            });
+            processor.process(queue, &mut encoder, self.meta_field.resolution());
+            queue.submit(Some(encoder.finish()));
+        }
+    }
+
+    fn triangle_area(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+        (b - a).perp_dot(c - a).abs() / 2.0
+    }
+
+    /// Area of the quads drawn as two triangle strip triangles each.
+    fn quads_area(quads: &[Quad]) -> f32 {
+        quads
+            .iter()
+            .map(|quad| quad.map(|v| v.as_vec2()))
+            .map(|[a, b, c, d]| triangle_area(a, b, c) + triangle_area(b, c, d))
+            .sum()
+    }
+
+    fn triangles_area(triangles: &[Triangle]) -> f32 {
+        triangles
+            .iter()
+            .map(|&[a, b, c]| triangle_area(a, b, c))
+            .sum()
+    }
+
+    // The GPU comparisons are ignored by default, run them with `cargo test -- --ignored` on a
+    // machine with a WebGPU compliant adapter.
+    fn headless_device() -> (wgpu::Device, wgpu::Queue) {
+        readback::headless_device().expect("no WebGPU compliant adapter available")
+    }
+
+    fn assert_scene(balls: &[MetaBall], boxes: &[MetaBox]) {
+        let (device, queue) = headless_device();
+
+        let scene = Scene::new(&device, &queue, balls, boxes);
+        let gpu_quads = scene.gpu_quads(&device, &queue);
+        let cpu_quads = scene.cpu_quads();
+
+        assert!(!cpu_quads.is_empty(), "scene produced no quads");
+        assert!(
+            quads_match(&gpu_quads, &cpu_quads, 1),
+            "GPU produced {} quads and CPU produced {}",
+            gpu_quads.len(),
+            cpu_quads.len()
+        );
+    }
+
+    fn ball(x: f32, y: f32, radius: f32) -> MetaBall {
+        MetaBall {
+            center: Vec2::new(x, y),
+            radius,
+            hidden: 0,
+        }
+    }
+
+    #[test]
+    fn field_edge_reads_zero() {
+        let resolution = UVec2::new(4, 3);
+        let field = vec![Vec2::new(1.0, 0.0); (resolution.x * resolution.y) as usize];
+
+        let quads = quads(&field, resolution, CELL_SIZE, IVec2::ZERO);
+
+        // The shape closes at the last sample instead of stretching over the edge cells.
+        let max = ((resolution - UVec2::ONE) * CELL_SIZE).as_ivec2();
+        assert!(
+            quads
+                .iter()
+                .flatten()
+                .all(|v| v.cmpge(IVec2::ZERO).all() && v.cmple(max).all())
+        );
+        assert_eq!(quads_area(&quads), max.as_vec2().element_product());
+    }
+
+    #[test]
+    #[ignore = "needs a WebGPU compliant adapter"]
+    fn single_ball_matches_gpu() {
+        assert_scene(&[ball(128.0, 96.0, 20.0)], &[]);
+    }
+
+    #[test]
+    #[ignore = "needs a WebGPU compliant adapter"]
+    fn overlapping_balls_match_gpu() {
+        assert_scene(&[ball(100.0, 90.0, 12.0), ball(150.0, 100.0, 18.0)], &[]);
+    }
+
+    #[test]
+    #[ignore = "needs a WebGPU compliant adapter"]
+    fn box_and_ball_match_gpu() {
+        assert_scene(
+            &[ball(200.0, 40.0, 10.0)],
+            &[MetaBox {
+                min: Vec2::new(40.0, 60.0),
+                max: Vec2::new(160.0, 140.0),
+                elevation: 8.0,
+                _padding: 0.0,
+            }],
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a WebGPU compliant adapter"]
+    fn shapes_past_the_edge_match_gpu() {
+        assert_scene(
+            &[ball(0.0, 0.0, 30.0), ball(256.0, 192.0, 30.0)],
+            &[MetaBox {
+                min: Vec2::new(-50.0, 120.0),
+                max: Vec2::new(60.0, 250.0),
+                elevation: 4.0,
+                _padding: 0.0,
+            }],
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a WebGPU compliant adapter"]
+    fn triangle_mesh_covers_the_quads() {
+        let (device, queue) = headless_device();
+
+        let scene = Scene::new(
+            &device,
+            &queue,
+            &[ball(200.0, 40.0, 10.0)],
+            &[MetaBox {
+                min: Vec2::new(40.0, 60.0),
+                max: Vec2::new(160.0, 140.0),
+                elevation: 8.0,
+                _padding: 0.0,
+            }],
+        );
+        let quads_area = quads_area(&scene.cpu_quads());
+        let triangles_area = triangles_area(&scene.gpu_triangles(&device, &queue));
+
+        // Quad vertices are truncated to whole pixels, the triangles are not.
+        assert!(
+            (quads_area - triangles_area).abs() < quads_area * 0.05,
+            "quads cover {quads_area} and triangles cover {triangles_area}"
+        );
+    }
+}
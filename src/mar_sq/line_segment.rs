@@ -1,7 +1,10 @@
 use glam::*;
 use wgpu::util::DeviceExt as _;
 
-use crate::meta_field::MetaField;
+use crate::{
+    meta_field::MetaField,
+    theme::{Theme, ThemePropertyName},
+};
 
 #[derive(Debug)]
 pub struct LineSegmentIndirect(wgpu::Buffer);
@@ -63,3 +66,25 @@ impl LineSegments {
         &self.0
     }
 }
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct OutlineStyle {
+    pub color: Vec4,
+    pub width: f32,
+    pub padding: Vec3,
+}
+
+impl OutlineStyle {
+    pub fn from_theme(color: ThemePropertyName, width: f32) -> Self {
+        Self {
+            color: Theme::current()
+                .properties()
+                .get(&color)
+                .and_then(|property| property.vec4())
+                .unwrap_or(Vec4::ONE),
+            width,
+            ..Default::default()
+        }
+    }
+}
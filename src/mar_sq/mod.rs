@@ -1,4 +1,8 @@
+pub mod contour;
+pub mod contour_line;
+pub mod cpu;
 pub mod line_segment;
 pub mod pipeline;
 pub mod quad;
 pub mod traits;
+pub mod triangle;
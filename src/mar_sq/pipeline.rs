@@ -3,7 +3,11 @@ use wgpu::util::DeviceExt as _;
 
 use crate::{
     frame::FrameMetadata,
+    light::LiquidLight,
+    liquid_material::LiquidMaterial,
     mar_sq::{
+        contour_line::{ContourLineStyle, ContourLines},
+        line_segment::{LineSegments, OutlineStyle},
         quad::Quads,
         traits::{
             MarchingSquaresShape, MarchingSquaresShapeBuffer as _,
@@ -123,6 +127,11 @@ impl<Shape: MarchingSquaresShape> MarchingSquaresProcessor<Shape> {
             ],
         });
 
+        let constants = [("workgroup_size", workgroup_size as f64)]
+            .into_iter()
+            .chain(shape.constants())
+            .collect::<Vec<_>>();
+
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Marching Squares Processor Pipeline Layout"),
             bind_group_layouts: &[&bind_group_layout],
@@ -135,7 +144,7 @@ impl<Shape: MarchingSquaresShape> MarchingSquaresProcessor<Shape> {
             module: &shader_module,
             entry_point: Some("main"),
             compilation_options: wgpu::PipelineCompilationOptions {
-                constants: &[("workgroup_size", workgroup_size as f64)],
+                constants: &constants,
                 ..Default::default()
             },
             cache: None,
@@ -206,6 +215,11 @@ impl<Shape: MarchingSquaresShape> MarchingSquaresProcessor<Shape> {
             1,
         );
     }
+
+    #[cfg(test)]
+    pub fn indirect_buffer(&self) -> &Shape::Indirect {
+        &self.indirect_buffer
+    }
 }
 
 #[derive(Debug)]
@@ -387,6 +401,16 @@ impl<Shape: MarchingSquaresShape> MarchingSquaresShapeRenderer<Shape> {
     }
 }
 
+/// What the liquid is shaded with, shared by the quad and fused liquid renderers.
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidShading<'a> {
+    pub background_color: Vec3,
+    pub material: &'a LiquidMaterial,
+    pub light: &'a LiquidLight,
+    /// Whether the refraction is split per colour channel.
+    pub dispersion: bool,
+}
+
 #[derive(Debug)]
 pub struct MarchingSquaresLiquidQuadRenderer {
     render_pipeline: wgpu::RenderPipeline,
@@ -397,6 +421,8 @@ pub struct MarchingSquaresLiquidQuadRenderer {
     background_bind_group_layout: wgpu::BindGroupLayout,
     background_bind_group: wgpu::BindGroup,
     background_color: wgpu::Buffer,
+    material: wgpu::Buffer,
+    light: wgpu::Buffer,
 }
 
 impl MarchingSquaresLiquidQuadRenderer {
@@ -406,18 +432,36 @@ impl MarchingSquaresLiquidQuadRenderer {
         quads: &Quads,
         meta_field: &MetaField,
         background_view: &wgpu::TextureView,
-        background_color: Vec3,
+        shading: LiquidShading,
         texture_format: wgpu::TextureFormat,
     ) -> Self {
         let background_color = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Marching Squares Liquid Quad Renderer Background Color Buffer"),
-            contents: bytemuck::bytes_of(&background_color),
+            contents: bytemuck::bytes_of(&shading.background_color),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let material = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Squares Liquid Quad Renderer Material Buffer"),
+            contents: bytemuck::bytes_of(shading.material),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let light = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Squares Liquid Quad Renderer Light Buffer"),
+            contents: bytemuck::bytes_of(shading.light),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
         let render_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Marching Squares Liquid Quad Renderer Shader Module"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shader/liquid_quad.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(
+                concat!(
+                    include_str!("../shader/liquid_quad.wgsl"),
+                    include_str!("../shader/liquid_shading.wgsl"),
+                )
+                .into(),
+            ),
         });
 
         let quads_bind_group_layout =
@@ -477,6 +521,26 @@ impl MarchingSquaresLiquidQuadRenderer {
                         },
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
@@ -506,6 +570,14 @@ impl MarchingSquaresLiquidQuadRenderer {
                         background_color.as_entire_buffer_binding(),
                     ),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Buffer(material.as_entire_buffer_binding()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::Buffer(light.as_entire_buffer_binding()),
+                },
             ],
         });
 
@@ -525,6 +597,7 @@ impl MarchingSquaresLiquidQuadRenderer {
                 ("base_radius", RADIUS),
                 ("fade_dist", FADE_DIST),
                 ("base_height", HEIGHT),
+                ("dispersion", if shading.dispersion { 1.0 } else { 0.0 }),
             ],
             ..Default::default()
         };
@@ -572,6 +645,8 @@ impl MarchingSquaresLiquidQuadRenderer {
             background_bind_group_layout,
             background_bind_group,
             background_color,
+            material,
+            light,
         }
     }
 
@@ -624,10 +699,28 @@ impl MarchingSquaresLiquidQuadRenderer {
                         self.background_color.as_entire_buffer_binding(),
                     ),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Buffer(
+                        self.material.as_entire_buffer_binding(),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::Buffer(self.light.as_entire_buffer_binding()),
+                },
             ],
         });
     }
 
+    pub fn set_material(&self, queue: &wgpu::Queue, material: &LiquidMaterial) {
+        queue.write_buffer(&self.material, 0, bytemuck::bytes_of(material));
+    }
+
+    pub fn set_light(&self, queue: &wgpu::Queue, light: &LiquidLight) {
+        queue.write_buffer(&self.light, 0, bytemuck::bytes_of(light));
+    }
+
     pub fn render(
         &self,
         encoder: &mut wgpu::CommandEncoder,
@@ -657,3 +750,670 @@ impl MarchingSquaresLiquidQuadRenderer {
         render_pass.draw_indirect(marching_squares_processor.indirect_buffer.buffer(), 0);
     }
 }
+
+#[derive(Debug)]
+pub struct MarchingSquaresContourLineRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    contour_lines_bind_group_layout: wgpu::BindGroupLayout,
+    contour_lines_bind_group: wgpu::BindGroup,
+    style_bind_group: wgpu::BindGroup,
+    style: wgpu::Buffer,
+}
+
+impl MarchingSquaresContourLineRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        contour_lines: &ContourLines,
+        style: ContourLineStyle,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let style = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Squares Contour Line Renderer Style Buffer"),
+            contents: bytemuck::bytes_of(&style),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let render_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Marching Squares Contour Line Renderer Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(ContourLines::RENDER_SHADER.into()),
+        });
+
+        let contour_lines_bind_group_layout =
+            MarchingSquaresShapeRenderer::<ContourLines>::create_bind_group_layout(
+                device,
+                Some("Marching Squares Contour Line Renderer Contour Lines Bind Group Layout"),
+            );
+
+        let contour_lines_bind_group =
+            MarchingSquaresShapeRenderer::<ContourLines>::create_bind_group(
+                device,
+                Some("Marching Squares Contour Line Renderer Contour Lines Bind Group"),
+                &contour_lines_bind_group_layout,
+                frame_metadata,
+                contour_lines,
+            );
+
+        let style_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Marching Squares Contour Line Renderer Style Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let style_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Marching Squares Contour Line Renderer Style Bind Group"),
+            layout: &style_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: style.as_entire_binding(),
+            }],
+        });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Marching Squares Contour Line Renderer Pipeline Layout"),
+                bind_group_layouts: &[&contour_lines_bind_group_layout, &style_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Marching Squares Contour Line Renderer Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &render_shader_module,
+                entry_point: Some("vert_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &render_shader_module,
+                entry_point: Some("frag_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: texture_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            render_pipeline,
+            contour_lines_bind_group_layout,
+            contour_lines_bind_group,
+            style_bind_group,
+            style,
+        }
+    }
+
+    pub fn recreate_bind_group(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        contour_lines: &ContourLines,
+    ) {
+        self.contour_lines_bind_group =
+            MarchingSquaresShapeRenderer::<ContourLines>::create_bind_group(
+                device,
+                Some("Marching Squares Contour Line Renderer Contour Lines Bind Group"),
+                &self.contour_lines_bind_group_layout,
+                frame_metadata,
+                contour_lines,
+            );
+    }
+
+    pub fn update_style(&self, queue: &wgpu::Queue, style: ContourLineStyle) {
+        queue.write_buffer(&self.style, 0, bytemuck::bytes_of(&style));
+    }
+
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        marching_squares_processor: &MarchingSquaresProcessor<ContourLines>,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Marching Squares Contour Line Renderer Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.contour_lines_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.style_bind_group, &[]);
+        render_pass.draw_indirect(marching_squares_processor.indirect_buffer.buffer(), 0);
+    }
+}
+
+#[derive(Debug)]
+pub struct MarchingSquaresOutlineRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    line_segments_bind_group_layout: wgpu::BindGroupLayout,
+    line_segments_bind_group: wgpu::BindGroup,
+    style_bind_group: wgpu::BindGroup,
+    style: wgpu::Buffer,
+}
+
+impl MarchingSquaresOutlineRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        line_segments: &LineSegments,
+        style: OutlineStyle,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let style = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Squares Outline Renderer Style Buffer"),
+            contents: bytemuck::bytes_of(&style),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let render_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Marching Squares Outline Renderer Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(
+                concat!(
+                    include_str!("../shader/outline.wgsl"),
+                    include_str!("../shader/segment.wgsl"),
+                )
+                .into(),
+            ),
+        });
+
+        let line_segments_bind_group_layout =
+            MarchingSquaresShapeRenderer::<LineSegments>::create_bind_group_layout(
+                device,
+                Some("Marching Squares Outline Renderer Line Segments Bind Group Layout"),
+            );
+
+        let line_segments_bind_group =
+            MarchingSquaresShapeRenderer::<LineSegments>::create_bind_group(
+                device,
+                Some("Marching Squares Outline Renderer Line Segments Bind Group"),
+                &line_segments_bind_group_layout,
+                frame_metadata,
+                line_segments,
+            );
+
+        let style_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Marching Squares Outline Renderer Style Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let style_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Marching Squares Outline Renderer Style Bind Group"),
+            layout: &style_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: style.as_entire_binding(),
+            }],
+        });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Marching Squares Outline Renderer Pipeline Layout"),
+                bind_group_layouts: &[&line_segments_bind_group_layout, &style_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Marching Squares Outline Renderer Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &render_shader_module,
+                entry_point: Some("vert_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &render_shader_module,
+                entry_point: Some("frag_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: texture_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            render_pipeline,
+            line_segments_bind_group_layout,
+            line_segments_bind_group,
+            style_bind_group,
+            style,
+        }
+    }
+
+    pub fn recreate_bind_group(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        line_segments: &LineSegments,
+    ) {
+        self.line_segments_bind_group =
+            MarchingSquaresShapeRenderer::<LineSegments>::create_bind_group(
+                device,
+                Some("Marching Squares Outline Renderer Line Segments Bind Group"),
+                &self.line_segments_bind_group_layout,
+                frame_metadata,
+                line_segments,
+            );
+    }
+
+    pub fn update_style(&self, queue: &wgpu::Queue, style: OutlineStyle) {
+        queue.write_buffer(&self.style, 0, bytemuck::bytes_of(&style));
+    }
+
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        marching_squares_processor: &MarchingSquaresProcessor<LineSegments>,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Marching Squares Outline Renderer Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.line_segments_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.style_bind_group, &[]);
+        render_pass.draw_indirect(marching_squares_processor.indirect_buffer.buffer(), 0);
+    }
+}
+
+/// Renders the liquid in a single full screen pass, classifying each pixel against the marching
+/// squares polygon of its field cell instead of drawing quads from [`MarchingSquaresProcessor`].
+#[derive(Debug)]
+pub struct MarchingSquaresLiquidFusedRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    frame_bind_group_layout: wgpu::BindGroupLayout,
+    frame_bind_group: wgpu::BindGroup,
+    meta_field_bind_group_layout: wgpu::BindGroupLayout,
+    meta_field_bind_group: wgpu::BindGroup,
+    background_bind_group_layout: wgpu::BindGroupLayout,
+    background_bind_group: wgpu::BindGroup,
+    background_color: wgpu::Buffer,
+    material: wgpu::Buffer,
+    light: wgpu::Buffer,
+}
+
+impl MarchingSquaresLiquidFusedRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+        background_view: &wgpu::TextureView,
+        shading: LiquidShading,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let background_color = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Background Color Buffer"),
+            contents: bytemuck::bytes_of(&shading.background_color),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let material = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Material Buffer"),
+            contents: bytemuck::bytes_of(shading.material),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let light = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Light Buffer"),
+            contents: bytemuck::bytes_of(shading.light),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let render_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(
+                concat!(
+                    include_str!("../shader/liquid_fused.wgsl"),
+                    include_str!("../shader/liquid_shading.wgsl"),
+                )
+                .into(),
+            ),
+        });
+
+        let frame_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Marching Squares Liquid Fused Renderer Frame Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let frame_bind_group =
+            Self::create_frame_bind_group(device, &frame_bind_group_layout, frame_metadata);
+
+        let meta_field_bind_group_layout =
+            MetaFieldRenderer::<MetaFieldMag>::create_bind_group_layout(
+                device,
+                Some("Marching Squares Liquid Fused Renderer Meta Field Bind Group Layout"),
+            );
+
+        let meta_field_bind_group = MetaFieldRenderer::<MetaFieldMag>::create_bind_group(
+            device,
+            Some("Marching Squares Liquid Fused Renderer Meta Field Bind Group"),
+            &meta_field_bind_group_layout,
+            meta_field,
+        );
+
+        let background_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Marching Squares Liquid Fused Renderer Background Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let background_bind_group = Self::create_background_bind_group(
+            device,
+            &background_bind_group_layout,
+            background_view,
+            &background_color,
+            &material,
+            &light,
+        );
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Marching Squares Liquid Fused Renderer Pipeline Layout"),
+                bind_group_layouts: &[
+                    &frame_bind_group_layout,
+                    &meta_field_bind_group_layout,
+                    &background_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
+
+        let compilation_options = wgpu::PipelineCompilationOptions {
+            constants: &[
+                ("base_radius", RADIUS),
+                ("fade_dist", FADE_DIST),
+                ("base_height", HEIGHT),
+                ("dispersion", if shading.dispersion { 1.0 } else { 0.0 }),
+            ],
+            ..Default::default()
+        };
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &render_shader_module,
+                entry_point: Some("vert_main"),
+                buffers: &[],
+                compilation_options: compilation_options.clone(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &render_shader_module,
+                entry_point: Some("frag_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: texture_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options,
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            render_pipeline,
+            frame_bind_group_layout,
+            frame_bind_group,
+            meta_field_bind_group_layout,
+            meta_field_bind_group,
+            background_bind_group_layout,
+            background_bind_group,
+            background_color,
+            material,
+            light,
+        }
+    }
+
+    pub fn recreate_bind_group(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+        background_view: &wgpu::TextureView,
+    ) {
+        self.frame_bind_group =
+            Self::create_frame_bind_group(device, &self.frame_bind_group_layout, frame_metadata);
+
+        self.meta_field_bind_group = MetaFieldRenderer::<MetaFieldMag>::create_bind_group(
+            device,
+            Some("Marching Squares Liquid Fused Renderer Meta Field Bind Group"),
+            &self.meta_field_bind_group_layout,
+            meta_field,
+        );
+
+        self.background_bind_group = Self::create_background_bind_group(
+            device,
+            &self.background_bind_group_layout,
+            background_view,
+            &self.background_color,
+            &self.material,
+            &self.light,
+        );
+    }
+
+    pub fn set_material(&self, queue: &wgpu::Queue, material: &LiquidMaterial) {
+        queue.write_buffer(&self.material, 0, bytemuck::bytes_of(material));
+    }
+
+    pub fn set_light(&self, queue: &wgpu::Queue, light: &LiquidLight) {
+        queue.write_buffer(&self.light, 0, bytemuck::bytes_of(light));
+    }
+
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.frame_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.meta_field_bind_group, &[]);
+        render_pass.set_bind_group(2, &self.background_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn create_frame_bind_group(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        frame_metadata: &FrameMetadata,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Frame Bind Group"),
+            layout: bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: frame_metadata.buffer().as_entire_binding(),
+            }],
+        })
+    }
+
+    fn create_background_bind_group(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        background_view: &wgpu::TextureView,
+        background_color: &wgpu::Buffer,
+        material: &wgpu::Buffer,
+        light: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Marching Squares Liquid Fused Renderer Background Bind Group"),
+            layout: bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(background_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&device.create_sampler(
+                        &wgpu::SamplerDescriptor {
+                            label: Some("Background Sampler"),
+                            mag_filter: wgpu::FilterMode::Linear,
+                            min_filter: wgpu::FilterMode::Linear,
+                            mipmap_filter: wgpu::FilterMode::Linear,
+                            ..Default::default()
+                        },
+                    )),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer(
+                        background_color.as_entire_buffer_binding(),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Buffer(material.as_entire_buffer_binding()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::Buffer(light.as_entire_buffer_binding()),
+                },
+            ],
+        })
+    }
+}
@@ -2,6 +2,8 @@ use glam::*;
 use wgpu::util::DeviceExt as _;
 
 use crate::meta_field::MetaField;
+#[cfg(test)]
+use crate::{mar_sq::cpu::Quad, readback};
 
 #[derive(Debug)]
 pub struct QuadIndirect(wgpu::Buffer);
@@ -18,7 +20,8 @@ impl QuadIndirect {
             }),
             usage: wgpu::BufferUsages::STORAGE
                 | wgpu::BufferUsages::INDIRECT
-                | wgpu::BufferUsages::COPY_DST,
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
         });
 
         Self(buffer)
@@ -62,4 +65,14 @@ impl Quads {
     pub fn buffer(&self) -> &wgpu::Buffer {
         &self.0
     }
+
+    #[cfg(test)]
+    pub async fn read_back(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        indirect: &QuadIndirect,
+    ) -> Result<Vec<Quad>, wgpu::BufferAsyncError> {
+        readback::read_instances(device, queue, indirect.buffer(), &self.0).await
+    }
 }
@@ -1,6 +1,8 @@
 use crate::mar_sq::{
+    contour_line::{ContourChannel, ContourLines},
     line_segment::{LineSegmentIndirect, LineSegments},
     quad::{QuadIndirect, Quads},
+    triangle::{TriangleIndirect, Triangles},
 };
 
 pub trait MarchingSquaresShape {
@@ -25,6 +27,26 @@ impl MarchingSquaresShape for Quads {
     const RENDER_SHADER: &'static str = include_str!("../shader/quad.wgsl");
 }
 
+impl MarchingSquaresShape for Triangles {
+    type Indirect = TriangleIndirect;
+    type Buffer = Triangles;
+    const PREPROCESS_SHADER: &'static str =
+        include_str!("../shader/triangle_marching_squares.wgsl");
+    const RENDER_SHADER: &'static str = include_str!("../shader/triangle.wgsl");
+}
+
+impl MarchingSquaresShape for ContourLines {
+    type Indirect = LineSegmentIndirect;
+    type Buffer = ContourLines;
+    const PREPROCESS_SHADER: &'static str =
+        include_str!("../shader/contour_line_marching_squares.wgsl");
+    /// Drawn by `MarchingSquaresContourLineRenderer`, which binds the style at group 1.
+    const RENDER_SHADER: &'static str = concat!(
+        include_str!("../shader/contour_line.wgsl"),
+        include_str!("../shader/segment.wgsl"),
+    );
+}
+
 pub trait MarchingSquaresShapeIndirect {
     fn new(device: &wgpu::Device) -> Self;
     fn buffer(&self) -> &wgpu::Buffer;
@@ -59,8 +81,27 @@ impl MarchingSquaresShapeIndirect for QuadIndirect {
     }
 }
 
+impl MarchingSquaresShapeIndirect for TriangleIndirect {
+    fn new(device: &wgpu::Device) -> Self {
+        TriangleIndirect::new(device)
+    }
+
+    fn buffer(&self) -> &wgpu::Buffer {
+        self.buffer()
+    }
+
+    fn reset(&self, queue: &wgpu::Queue) {
+        self.reset(queue)
+    }
+}
+
 pub trait MarchingSquaresShapeBuffer {
     fn buffer(&self) -> &wgpu::Buffer;
+
+    /// Extra override constants for the preprocess shader.
+    fn constants(&self) -> Vec<(&'static str, f64)> {
+        Vec::new()
+    }
 }
 
 impl MarchingSquaresShapeBuffer for LineSegments {
@@ -74,3 +115,30 @@ impl MarchingSquaresShapeBuffer for Quads {
         self.buffer()
     }
 }
+
+impl MarchingSquaresShapeBuffer for Triangles {
+    fn buffer(&self) -> &wgpu::Buffer {
+        self.buffer()
+    }
+}
+
+impl MarchingSquaresShapeBuffer for ContourLines {
+    fn buffer(&self) -> &wgpu::Buffer {
+        self.buffer()
+    }
+
+    fn constants(&self) -> Vec<(&'static str, f64)> {
+        let levels = self.levels();
+        let channel = match levels.channel {
+            ContourChannel::Magnitude => 0,
+            ContourChannel::Elevation => 1,
+        };
+
+        vec![
+            ("level_channel", channel as f64),
+            ("level_count", levels.count as f64),
+            ("level_start", levels.start as f64),
+            ("level_step", levels.step as f64),
+        ]
+    }
+}
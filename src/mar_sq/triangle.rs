@@ -0,0 +1,82 @@
+use glam::*;
+use wgpu::util::DeviceExt as _;
+
+use crate::meta_field::MetaField;
+#[cfg(test)]
+use crate::readback;
+
+/// Three pixel positions of a triangle, the same layout as an instance in [`Triangles`].
+pub type Triangle = [Vec2; 3];
+
+#[derive(Debug)]
+pub struct TriangleIndirect(wgpu::Buffer);
+
+impl TriangleIndirect {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Triangle Indirect Buffer"),
+            contents: bytemuck::bytes_of(&wgpu::util::DrawIndirectArgs {
+                vertex_count: 3,
+                instance_count: 0,
+                first_vertex: 0,
+                first_instance: 0,
+            }),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::INDIRECT
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+        });
+
+        Self(buffer)
+    }
+
+    pub fn reset(&self, queue: &wgpu::Queue) {
+        queue.write_buffer(
+            &self.0,
+            0,
+            bytemuck::bytes_of(&wgpu::util::DrawIndirectArgs {
+                vertex_count: 3,
+                instance_count: 0,
+                first_vertex: 0,
+                first_instance: 0,
+            }),
+        );
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct Triangles(wgpu::Buffer);
+
+impl Triangles {
+    pub fn new(device: &wgpu::Device, meta_field: &MetaField) -> Self {
+        // A saddle cell is a hexagon, which is at most 4 triangles.
+        let max_count = (meta_field.resolution().x - 1) * (meta_field.resolution().y - 1) * 4;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Triangle Buffer"),
+            size: (std::mem::size_of::<Triangle>() * max_count as usize) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        Self(buffer)
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.0
+    }
+
+    #[cfg(test)]
+    pub async fn read_back(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        indirect: &TriangleIndirect,
+    ) -> Result<Vec<Triangle>, wgpu::BufferAsyncError> {
+        readback::read_instances(device, queue, indirect.buffer(), &self.0).await
+    }
+}
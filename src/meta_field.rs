@@ -1,7 +1,11 @@
 use glam::*;
 use wgpu::util::DeviceExt as _;
 
-use crate::frame::FrameMetadata;
+use crate::{
+    frame::FrameMetadata,
+    meta_shape::{MetaBall, MetaBox},
+    pipeline::{FADE_DIST, RADIUS},
+};
 
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
@@ -89,3 +93,255 @@ impl MetaField {
         &self.buffer
     }
 }
+
+const MAX_MAG: f32 = 1e4;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MetaFieldSample {
+    pub magnitude: f32,
+    pub elevation: f32,
+    pub gradient: Vec2,
+}
+
+impl MetaFieldSample {
+    /// Evaluates the field on the CPU, mirroring `meta_field_process.wgsl`.
+    ///
+    /// `pos` is in page coordinates, the same space as the shapes.
+    pub fn sample(balls: &[MetaBall], boxes: &[MetaBox], pos: Vec2) -> Self {
+        let base_radius = RADIUS as f32;
+
+        let mut magnitude = 0.0;
+        let mut elevation = 0.0;
+        let mut gradient = Vec2::ZERO;
+
+        for ball in balls.iter().filter(|ball| ball.hidden == 0) {
+            let disp = pos - ball.center;
+            let dist = (disp.length() - ball.radius).max(0.0);
+            magnitude += implicit(dist, base_radius);
+            gradient += disp.normalize_or_zero() * differentiate_implicit(dist, base_radius);
+        }
+
+        for meta_box in boxes {
+            let disp = pos - pos.clamp(meta_box.min, meta_box.max);
+            let dist = disp.length();
+            let radius = base_radius + meta_box.elevation;
+            magnitude += implicit(dist, radius);
+            elevation += smooth_elevation(dist, meta_box.elevation, radius);
+            gradient += disp.normalize_or_zero() * differentiate_implicit(dist, radius);
+        }
+
+        if magnitude >= MAX_MAG {
+            gradient = Vec2::ZERO;
+        }
+
+        Self {
+            magnitude: magnitude.min(MAX_MAG),
+            elevation: elevation.min(MAX_MAG),
+            gradient,
+        }
+    }
+
+    /// Whether the sample is on or inside the iso line the liquid is drawn at.
+    pub fn is_inside(&self) -> bool {
+        self.magnitude >= 1.0
+    }
+}
+
+fn implicit(dist: f32, radius: f32) -> f32 {
+    if dist == 0.0 {
+        return MAX_MAG;
+    }
+
+    let implicit = radius * radius / (dist * dist);
+    let fade_factor = ((dist - radius) / FADE_DIST as f32).min(1.0);
+    (implicit * (1.0 - fade_factor)).min(MAX_MAG)
+}
+
+fn differentiate_implicit(dist: f32, radius: f32) -> f32 {
+    let fade_dist = FADE_DIST as f32;
+    if dist == 0.0 || dist - radius >= fade_dist || implicit(dist, radius) >= MAX_MAG {
+        return 0.0;
+    }
+
+    let dist_sq = dist * dist;
+    let fade = 1.0 - (dist - radius) / fade_dist;
+    -radius * radius * (2.0 * fade / (dist_sq * dist) + 1.0 / (fade_dist * dist_sq))
+}
+
+fn smooth_elevation(dist: f32, elevation: f32, radius: f32) -> f32 {
+    let fade_dist = FADE_DIST as f32;
+    if dist <= radius {
+        elevation
+    } else if dist >= radius + fade_dist {
+        0.0
+    } else {
+        elevation * (1.0 - (dist - radius) / fade_dist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn ball(center: Vec2, radius: f32) -> MetaBall {
+        MetaBall {
+            center,
+            radius,
+            hidden: 0,
+        }
+    }
+
+    fn assert_sample(sample: MetaFieldSample, magnitude: f32, elevation: f32, gradient: Vec2) {
+        assert!(
+            (sample.magnitude - magnitude).abs() < EPSILON,
+            "magnitude {} != {magnitude}",
+            sample.magnitude
+        );
+        assert!(
+            (sample.elevation - elevation).abs() < EPSILON,
+            "elevation {} != {elevation}",
+            sample.elevation
+        );
+        assert!(
+            sample.gradient.abs_diff_eq(gradient, EPSILON),
+            "gradient {} != {gradient}",
+            sample.gradient
+        );
+    }
+
+    #[test]
+    fn single_ball() {
+        let balls = [ball(Vec2::ZERO, 10.0)];
+
+        // On the iso line, one radius away from the edge of the ball.
+        assert_sample(
+            MetaFieldSample::sample(&balls, &[], Vec2::new(46.0, 0.0)),
+            1.0,
+            0.0,
+            Vec2::new(-(2.0 / 36.0 + 1.0 / 24.0), 0.0),
+        );
+
+        // Halfway in, where the fade factor goes negative.
+        assert_sample(
+            MetaFieldSample::sample(&balls, &[], Vec2::new(0.0, 28.0)),
+            7.0,
+            0.0,
+            Vec2::new(0.0, -(2.0 * 1.75 * 4.0 / 18.0 + 4.0 / 24.0)),
+        );
+
+        // Past the fade distance.
+        assert_sample(
+            MetaFieldSample::sample(&balls, &[], Vec2::new(70.0, 0.0)),
+            0.0,
+            0.0,
+            Vec2::ZERO,
+        );
+
+        // Inside the ball the field saturates.
+        assert_sample(
+            MetaFieldSample::sample(&balls, &[], Vec2::new(5.0, 0.0)),
+            MAX_MAG,
+            0.0,
+            Vec2::ZERO,
+        );
+    }
+
+    #[test]
+    fn hidden_ball() {
+        let balls = [MetaBall {
+            hidden: 1,
+            ..ball(Vec2::ZERO, 10.0)
+        }];
+
+        assert_sample(
+            MetaFieldSample::sample(&balls, &[], Vec2::new(46.0, 0.0)),
+            0.0,
+            0.0,
+            Vec2::ZERO,
+        );
+    }
+
+    #[test]
+    fn single_box() {
+        let boxes = [MetaBox {
+            min: Vec2::new(0.0, 0.0),
+            max: Vec2::new(100.0, 50.0),
+            elevation: 8.0,
+            _padding: 0.0,
+        }];
+
+        assert_sample(
+            MetaFieldSample::sample(&[], &boxes, Vec2::new(50.0, 25.0)),
+            MAX_MAG,
+            8.0,
+            Vec2::ZERO,
+        );
+
+        // The elevation widens the radius to 44.
+        assert_sample(
+            MetaFieldSample::sample(&[], &boxes, Vec2::new(144.0, 25.0)),
+            1.0,
+            8.0,
+            Vec2::new(-(2.0 / 44.0 + 1.0 / 24.0), 0.0),
+        );
+
+        // Halfway through the fade.
+        let fade = 0.5;
+        let implicit = (44.0f32 / 56.0).powi(2);
+        assert_sample(
+            MetaFieldSample::sample(&[], &boxes, Vec2::new(50.0, 106.0)),
+            implicit * fade,
+            4.0,
+            Vec2::new(
+                0.0,
+                -44.0 * 44.0 * (2.0 * fade / 56.0f32.powi(3) + 1.0 / (24.0 * 56.0 * 56.0)),
+            ),
+        );
+    }
+
+    #[test]
+    fn overlapping_balls() {
+        let balls = [ball(Vec2::ZERO, 0.0), ball(Vec2::new(60.0, 0.0), 0.0)];
+
+        // Each ball contributes 1.44 * 1.25 and their gradients cancel out.
+        assert_sample(
+            MetaFieldSample::sample(&balls, &[], Vec2::new(30.0, 0.0)),
+            3.6,
+            0.0,
+            Vec2::ZERO,
+        );
+
+        // Off the axis only the vertical components add up.
+        let sample = MetaFieldSample::sample(&balls, &[], Vec2::new(30.0, 40.0));
+        let single = MetaFieldSample::sample(&balls[..1], &[], Vec2::new(30.0, 40.0));
+        assert_sample(
+            sample,
+            single.magnitude * 2.0,
+            0.0,
+            Vec2::new(0.0, single.gradient.y * 2.0),
+        );
+    }
+    #[test]
+    fn inside_iso_line() {
+        let balls = [ball(Vec2::ZERO, 10.0)];
+        let boxes = [MetaBox {
+            min: Vec2::new(200.0, 0.0),
+            max: Vec2::new(300.0, 50.0),
+            elevation: 8.0,
+            _padding: 0.0,
+        }];
+
+        let is_inside = |pos| MetaFieldSample::sample(&balls, &boxes, pos).is_inside();
+
+        assert!(is_inside(Vec2::ZERO));
+        assert!(is_inside(Vec2::new(45.0, 0.0)));
+        assert!(!is_inside(Vec2::new(47.0, 0.0)));
+        assert!(!is_inside(Vec2::new(0.0, -100.0)));
+
+        // The elevation widens the radius of the box to 44.
+        assert!(is_inside(Vec2::new(250.0, 93.0)));
+        assert!(!is_inside(Vec2::new(250.0, 95.0)));
+    }
+}
@@ -2,7 +2,10 @@ use glam::*;
 use wasm_bindgen::UnwrapThrowExt as _;
 use wgpu::util::DeviceExt as _;
 
-use crate::{controller::PanelController, ext::WindowExt as _};
+use crate::{
+    controller::PanelController, ext::WindowExt as _, impulse::Impulses,
+    meta_field::MetaFieldSample,
+};
 
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
@@ -42,7 +45,8 @@ impl MetaShapes {
         panel_controller: &mut PanelController,
     ) -> Self {
         let window = web_sys::window().expect_throw("window");
-        let mut this = MetaShapes::new(device, 1, panel_controller.panel_count());
+        // The mouse and the hovered skill icon
+        let mut this = MetaShapes::new(device, 2, panel_controller.panel_count());
         panel_controller.resize(&mut this, window.scroll_pos());
         this
     }
@@ -86,11 +90,38 @@ impl MetaShapes {
         &mut self.boxes
     }
 
+    pub fn sample(&self, pos: Vec2) -> MetaFieldSample {
+        MetaFieldSample::sample(&self.balls, &self.boxes, pos)
+    }
+
+    /// Whether a page position is inside the liquid, see [`MetaFieldSample::is_inside`].
+    pub fn is_inside(&self, pos: Vec2) -> bool {
+        self.sample(pos).is_inside()
+    }
+
     pub fn ensure_buffer(&mut self, queue: &wgpu::Queue) {
         queue.write_buffer(&self.balls_buffer, 0, bytemuck::cast_slice(&self.balls));
         queue.write_buffer(&self.boxes_buffer, 0, bytemuck::cast_slice(&self.boxes));
     }
 
+    /// Uploads the shapes pushed along by the passing impulses.
+    ///
+    /// Only the uploaded copies are displaced, so shapes that are not placed again every frame
+    /// still return to rest once the impulses die out.
+    pub fn ensure_buffer_displaced(&mut self, queue: &wgpu::Queue, impulses: &Impulses) {
+        if impulses.impulses().is_empty() {
+            self.ensure_buffer(queue);
+            return;
+        }
+
+        let mut balls = self.balls.clone();
+        let mut boxes = self.boxes.clone();
+        impulses.displace(&mut balls, &mut boxes);
+
+        queue.write_buffer(&self.balls_buffer, 0, bytemuck::cast_slice(&balls));
+        queue.write_buffer(&self.boxes_buffer, 0, bytemuck::cast_slice(&boxes));
+    }
+
     pub fn balls_buffer(&self) -> &wgpu::Buffer {
         &self.balls_buffer
     }
@@ -1,12 +1,18 @@
+use ahash::HashMap;
 use glam::*;
 use vello_svg::vello;
 use wasm_bindgen::{JsCast as _, UnwrapThrowExt};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::js_sys;
-use wgpu::util::DeviceExt as _;
 
-use crate::texture_blitter::TextureBlitter;
-use crate::{frame::FrameMetadata, meta_field::MetaField, meta_shape::MetaShapes};
+use crate::texture_blitter::{TextureBlit, TextureBlitter};
+use crate::{
+    frame::FrameMetadata,
+    meta_field::MetaField,
+    meta_shape::MetaShapes,
+    mipmap::{MipmapGenerator, mip_level_count},
+    theme::Theme,
+};
 
 pub const RADIUS: f64 = 36.0;
 pub const FADE_DIST: f64 = 24.0;
@@ -419,18 +425,130 @@ impl<T: MetaFieldRenderType> MetaFieldRenderer<T> {
     }
 }
 
+/// What the intermediate texture of a [`BackgroundSvgRenderer`] was last rasterised for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SvgRasterKey {
+    size: UVec2,
+    pixel_ratio: f32,
+    theme: Theme,
+}
+
+/// Longest side an SVG is rasterised at.
+const MAX_SVG_RASTER_SIZE: u32 = 4096;
+
+/// How far the wanted raster size can drift from the allocated one before reallocating.
+const SVG_RASTER_REALLOCATE_RATIO: f32 = 1.25;
+
+/// An element of an SVG with an id.
+#[derive(Debug, Clone)]
+pub struct SvgElement {
+    pub id: String,
+    /// The `data-name` attribute or `<title>` child of the element.
+    pub name: Option<String>,
+    /// Bounds relative to the SVG, from `0` to `1`.
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl SvgElement {
+    /// Collects the outermost elements with an id.
+    ///
+    /// The names are read from `svg`, as the parsed tree drops unknown attributes and titles.
+    fn collect(tree: &vello_svg::usvg::Tree, svg: &str) -> Vec<Self> {
+        fn visit(
+            group: &vello_svg::usvg::Group,
+            size: Vec2,
+            names: &HashMap<String, String>,
+            elements: &mut Vec<SvgElement>,
+        ) {
+            for node in group.children() {
+                if !node.id().is_empty() {
+                    let rect = node.abs_bounding_box();
+                    elements.push(SvgElement {
+                        id: node.id().to_owned(),
+                        name: names.get(node.id()).cloned(),
+                        min: Vec2::new(rect.left(), rect.top()) / size,
+                        max: Vec2::new(rect.right(), rect.bottom()) / size,
+                    });
+                } else if let vello_svg::usvg::Node::Group(group) = node {
+                    visit(group, size, names, elements);
+                }
+            }
+        }
+
+        let size = Vec2::new(tree.size().width(), tree.size().height());
+        let names = Self::names(svg);
+        let mut elements = Vec::new();
+        visit(tree.root(), size, &names, &mut elements);
+        elements
+    }
+
+    /// Names of the elements with an id, from `data-name` or else a `<title>` child.
+    fn names(svg: &str) -> HashMap<String, String> {
+        use vello_svg::usvg::roxmltree;
+
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document = match roxmltree::Document::parse_with_options(svg, options) {
+            Ok(document) => document,
+            Err(e) => {
+                log::error!("Failed to read SVG element names: {e}");
+                return HashMap::default();
+            }
+        };
+
+        document
+            .descendants()
+            .filter_map(|node| {
+                let id = node.attribute("id")?;
+                let name = node.attribute("data-name").or_else(|| {
+                    node.children()
+                        .find(|child| child.has_tag_name("title"))
+                        .and_then(|title| title.text())
+                })?;
+                Some((id.to_owned(), name.trim().to_owned()))
+            })
+            .collect()
+    }
+}
+
+/// Creates the vello renderer shared by every [`BackgroundSvgRenderer`].
+pub fn create_vello_renderer(device: &wgpu::Device) -> vello::Renderer {
+    vello::Renderer::new(
+        device,
+        vello::RendererOptions {
+            use_cpu: false,
+            antialiasing_support: vello::AaSupport::area_only(),
+            num_init_threads: None,
+            pipeline_cache: None,
+        },
+    )
+    .expect_throw("vello renderer")
+}
+
 pub struct BackgroundSvgRenderer {
+    svg: String,
     scene: vello::Scene,
-    renderer: vello::Renderer,
+    scene_theme: Theme,
+    elements: Vec<SvgElement>,
     background_blitter: TextureBlitter,
+    mipmap_generator: MipmapGenerator,
     intermediate_texture: wgpu::Texture,
+    svg_size: UVec2,
+    raster_key: Option<SvgRasterKey>,
 }
 
 impl BackgroundSvgRenderer {
-    pub async fn new_skills(device: &wgpu::Device, texture_format: wgpu::TextureFormat) -> Self {
+    pub async fn fetch(
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        url: &str,
+    ) -> Self {
         let window = web_sys::window().expect_throw("window");
 
-        let response = JsFuture::from(window.fetch_with_str("skills.svg"))
+        let response = JsFuture::from(window.fetch_with_str(url))
             .await
             .unwrap_throw()
             .dyn_into::<web_sys::Response>()
@@ -441,73 +559,159 @@ impl BackgroundSvgRenderer {
             .as_string()
             .unwrap_throw();
 
-        // Matches the SVG size
-        const INTERMEDIATE_SIZE: UVec2 = UVec2::new(1200, 900);
-
-        Self::new(device, texture_format, INTERMEDIATE_SIZE, &svg)
+        Self::new(device, texture_format, &svg)
     }
 
-    pub fn new(
-        device: &wgpu::Device,
-        texture_format: wgpu::TextureFormat,
-        intermediate_size: UVec2,
-        svg: &str,
-    ) -> Self {
-        let scene = vello_svg::render(svg).expect_throw("background svg");
+    pub fn new(device: &wgpu::Device, texture_format: wgpu::TextureFormat, svg: &str) -> Self {
+        let scene_theme = Theme::current();
+        let tree = Self::parse(svg, scene_theme);
+        let elements = SvgElement::collect(&tree, svg);
+        let scene = vello_svg::render_tree(&tree);
 
-        let renderer = vello::Renderer::new(
-            device,
-            vello::RendererOptions {
-                use_cpu: false,
-                antialiasing_support: vello::AaSupport::area_only(),
-                num_init_threads: None,
-                pipeline_cache: None,
-            },
+        let svg_size = Vec2::new(tree.size().width(), tree.size().height())
+            .ceil()
+            .as_uvec2()
+            .max(UVec2::ONE);
+
+        let intermediate_texture = Self::create_intermediate_texture(device, svg_size);
+
+        let background_blitter = TextureBlitter::new(device, texture_format);
+
+        let mipmap_generator = MipmapGenerator::new(device, wgpu::TextureFormat::Rgba8Unorm);
+
+        Self {
+            svg: svg.to_owned(),
+            scene,
+            scene_theme,
+            elements,
+            background_blitter,
+            mipmap_generator,
+            intermediate_texture,
+            svg_size,
+            raster_key: None,
+        }
+    }
+
+    fn parse(svg: &str, theme: Theme) -> vello_svg::usvg::Tree {
+        vello_svg::usvg::Tree::from_str(
+            &theme.recolor_svg(svg),
+            &vello_svg::usvg::Options::default(),
         )
-        .expect_throw("vello renderer");
+        .expect_throw("background svg")
+    }
 
-        let intermediate_texture = device.create_texture(&wgpu::TextureDescriptor {
+    fn create_intermediate_texture(device: &wgpu::Device, size: UVec2) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Background SVG Intermediate Texture"),
             size: wgpu::Extent3d {
-                width: intermediate_size.x,
-                height: intermediate_size.y,
+                width: size.x,
+                height: size.y,
                 depth_or_array_layers: 1,
             },
-            mip_level_count: 1,
+            mip_level_count: mip_level_count(size.x, size.y),
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+            usage: wgpu::TextureUsages::STORAGE_BINDING
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
             view_formats: &[],
-        });
+        })
+    }
 
-        let background_blitter = TextureBlitter::new(device, texture_format);
+    /// Raster size for showing the SVG at `target_size` on screen, keeping its aspect ratio.
+    fn raster_size(&self, device: &wgpu::Device, target_size: Vec2, pixel_ratio: f32) -> UVec2 {
+        let svg_size = self.svg_size.as_vec2();
+        let max_size = MAX_SVG_RASTER_SIZE.min(device.limits().max_texture_dimension_2d) as f32;
 
-        Self {
-            scene,
-            renderer,
-            background_blitter,
-            intermediate_texture,
-        }
+        let scale = ((target_size / svg_size).max_element() * pixel_ratio)
+            .min(max_size / svg_size.max_element());
+
+        (svg_size * scale)
+            .ceil()
+            .as_uvec2()
+            .clamp(UVec2::ONE, UVec2::splat(max_size as u32))
     }
 
-    pub fn render(
+    /// Rasterises the SVG again if its size on screen or the theme changed, then prepares the
+    /// blit drawn by [`BackgroundSvgRenderer::render`].
+    pub fn prepare(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         encoder: &mut wgpu::CommandEncoder,
-        background_view: &wgpu::TextureView,
+        vello_renderer: &mut vello::Renderer,
         frame_metadata: &FrameMetadata,
-        top_left: IVec2,
-        bottom_right: IVec2,
+        blit: &TextureBlit,
     ) {
-        if let Err(e) = self.renderer.render_to_texture(
+        let pixel_ratio = web_sys::window()
+            .expect_throw("window")
+            .device_pixel_ratio() as f32;
+
+        let raster_size = self.raster_size(device, blit.size, pixel_ratio);
+        let ratio = raster_size.as_vec2() / self.raster_texture_size().as_vec2();
+        if ratio.max_element() > SVG_RASTER_REALLOCATE_RATIO
+            || ratio.min_element() < 1.0 / SVG_RASTER_REALLOCATE_RATIO
+        {
+            self.intermediate_texture = Self::create_intermediate_texture(device, raster_size);
+        }
+
+        let raster_key = SvgRasterKey {
+            size: self.raster_texture_size(),
+            pixel_ratio,
+            theme: Theme::current(),
+        };
+        if self.raster_key != Some(raster_key) {
+            if self.scene_theme != raster_key.theme {
+                self.scene = vello_svg::render_tree(&Self::parse(&self.svg, raster_key.theme));
+                self.scene_theme = raster_key.theme;
+            }
+
+            self.rasterize(device, queue, encoder, vello_renderer);
+            self.raster_key = Some(raster_key);
+        }
+
+        self.background_blitter.prepare_blit(
             device,
             queue,
-            &self.scene,
             &self
                 .intermediate_texture
                 .create_view(&wgpu::TextureViewDescriptor::default()),
+            frame_metadata,
+            blit,
+        );
+    }
+
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, background_view: &wgpu::TextureView) {
+        self.background_blitter.blit(encoder, background_view);
+    }
+
+    /// Renders the scene into the intermediate texture and fills its mip chain.
+    fn rasterize(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        vello_renderer: &mut vello::Renderer,
+    ) {
+        let scale = self.raster_texture_size().as_dvec2() / self.svg_size.as_dvec2();
+
+        let mut scene = vello::Scene::new();
+        scene.append(
+            &self.scene,
+            Some(vello::kurbo::Affine::scale_non_uniform(scale.x, scale.y)),
+        );
+
+        if let Err(e) = vello_renderer.render_to_texture(
+            device,
+            queue,
+            &scene,
+            &self
+                .intermediate_texture
+                .create_view(&wgpu::TextureViewDescriptor {
+                    mip_level_count: Some(1),
+                    ..Default::default()
+                }),
             &vello::RenderParams {
                 base_color: vello::peniko::color::palette::css::TRANSPARENT,
                 width: self.intermediate_texture.size().width,
@@ -518,94 +722,173 @@ impl BackgroundSvgRenderer {
             log::error!("Failed to render background SVG: {e}");
         }
 
-        self.background_blitter.copy(
-            device,
-            queue,
-            encoder,
-            &self
-                .intermediate_texture
-                .create_view(&wgpu::TextureViewDescriptor::default()),
-            background_view,
-            frame_metadata,
-            top_left,
-            (bottom_right - top_left).as_vec2()
-                / Vec2::new(
-                    self.intermediate_texture.size().width as f32,
-                    self.intermediate_texture.size().height as f32,
-                ),
-        );
+        self.mipmap_generator
+            .generate(device, encoder, &self.intermediate_texture);
     }
+
+    pub fn elements(&self) -> &[SvgElement] {
+        &self.elements
+    }
+
+    /// Size of the SVG itself, independent of the resolution it is rasterised at.
+    pub fn size(&self) -> UVec2 {
+        self.svg_size
+    }
+
+    fn raster_texture_size(&self) -> UVec2 {
+        UVec2::new(
+            self.intermediate_texture.size().width,
+            self.intermediate_texture.size().height,
+        )
+    }
+}
+
+/// Shortest time a frame of an animated image is shown for, like browsers do.
+const MIN_FRAME_DURATION: f32 = 0.02;
+
+/// A decoded frame of an animated image.
+#[derive(Debug)]
+pub struct ImageFrame {
+    pixels: image::RgbaImage,
+    /// Seconds the frame is shown for.
+    duration: f32,
+}
+
+/// An image decoded away from the render loop, see [`BackgroundImageRenderer::load`].
+#[derive(Debug)]
+pub enum DecodedImage {
+    /// Decoded by the browser.
+    Bitmap(web_sys::ImageBitmap),
+    /// Every frame of an animated WebP, the browser only gives the first one as a bitmap.
+    Frames(Vec<ImageFrame>),
 }
 
 #[derive(Debug)]
 pub struct BackgroundImageRenderer {
     background_blitter: TextureBlitter,
+    mipmap_generator: MipmapGenerator,
     image: wgpu::Texture,
     size: UVec2,
+    /// Empty for still images.
+    frames: Vec<ImageFrame>,
+    frame_index: usize,
+    frame_elapsed: f32,
 }
 
 impl BackgroundImageRenderer {
-    pub async fn new_background(
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        texture_format: wgpu::TextureFormat,
-    ) -> Self {
+    /// Fetches and decodes an image, for loading it in the background.
+    ///
+    /// Fails if the image cannot be fetched or decoded.
+    pub async fn load(url: &str) -> Result<DecodedImage, wasm_bindgen::JsValue> {
         let window = web_sys::window().expect_throw("window");
 
-        let response = JsFuture::from(window.fetch_with_str("background.webp"))
-            .await
-            .unwrap_throw()
-            .dyn_into::<web_sys::Response>()
-            .unwrap_throw();
-        let bytes = JsFuture::from(response.array_buffer().unwrap_throw())
-            .await
-            .unwrap_throw()
-            .dyn_into::<js_sys::ArrayBuffer>()
-            .unwrap_throw();
-        let bytes = js_sys::Uint8Array::new(&bytes).to_vec();
+        let response = JsFuture::from(window.fetch_with_str(url))
+            .await?
+            .dyn_into::<web_sys::Response>()?;
+        if !response.ok() {
+            return Err(format!("{url}: {}", response.status()).into());
+        }
+
+        let bytes = JsFuture::from(response.array_buffer()?)
+            .await?
+            .dyn_into::<js_sys::ArrayBuffer>()?;
+        let bytes = js_sys::Uint8Array::new(&bytes);
 
-        Self::new(device, queue, texture_format, &bytes)
+        if let Some(frames) = Self::decode_animation(&bytes.to_vec()) {
+            return Ok(DecodedImage::Frames(frames));
+        }
+
+        let blob = web_sys::Blob::new_with_u8_array_sequence(&js_sys::Array::of1(&bytes))?;
+        let bitmap = JsFuture::from(window.create_image_bitmap_with_blob(&blob)?)
+            .await?
+            .dyn_into::<web_sys::ImageBitmap>()?;
+        Ok(DecodedImage::Bitmap(bitmap))
     }
 
     pub fn new(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         texture_format: wgpu::TextureFormat,
-        data: &[u8],
+        image: DecodedImage,
     ) -> Self {
-        let bytes = image::load_from_memory(data)
-            .expect("load background image")
-            .to_rgba8();
-
-        let (width, height) = bytes.dimensions();
-        let size = UVec2::new(width, height);
+        let size = match &image {
+            DecodedImage::Bitmap(bitmap) => UVec2::new(bitmap.width(), bitmap.height()),
+            DecodedImage::Frames(frames) => frames[0].pixels.dimensions().into(),
+        }
+        .max(UVec2::ONE);
 
-        let image = device.create_texture_with_data(
-            queue,
-            &wgpu::TextureDescriptor {
-                label: Some("Background Image Texture"),
-                size: wgpu::Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8Unorm,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING,
-                view_formats: &[],
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Background Image Texture"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
             },
-            wgpu::util::TextureDataOrder::default(),
-            &bytes,
-        );
+            mip_level_count: mip_level_count(size.x, size.y),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        let mipmap_generator = MipmapGenerator::new(device, wgpu::TextureFormat::Rgba8Unorm);
+
+        let frames = match image {
+            DecodedImage::Bitmap(bitmap) => {
+                Self::upload_bitmap(device, queue, &mipmap_generator, &texture, &bitmap);
+                bitmap.close();
+                Vec::new()
+            }
+            DecodedImage::Frames(frames) => {
+                Self::upload(
+                    device,
+                    queue,
+                    &mipmap_generator,
+                    &texture,
+                    &frames[0].pixels,
+                );
+                frames
+            }
+        };
 
         let background_blitter = TextureBlitter::new(device, texture_format);
 
         Self {
             background_blitter,
-            image,
+            mipmap_generator,
+            image: texture,
             size,
+            frames,
+            frame_index: 0,
+            frame_elapsed: 0.0,
+        }
+    }
+
+    /// Advances animated images, uploading the next frame when it is due.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, delta_time: f32) {
+        if self.frames.is_empty() {
+            return;
+        }
+
+        let frame_index = self.frame_index;
+
+        self.frame_elapsed += delta_time;
+        while self.frame_elapsed >= self.frames[self.frame_index].duration {
+            self.frame_elapsed -= self.frames[self.frame_index].duration;
+            self.frame_index = (self.frame_index + 1) % self.frames.len();
+        }
+
+        if self.frame_index != frame_index {
+            Self::upload(
+                device,
+                queue,
+                &self.mipmap_generator,
+                &self.image,
+                &self.frames[self.frame_index].pixels,
+            );
         }
     }
 
@@ -616,311 +899,288 @@ impl BackgroundImageRenderer {
         encoder: &mut wgpu::CommandEncoder,
         background_view: &wgpu::TextureView,
         frame_metadata: &FrameMetadata,
-        offset: IVec2,
+        blit: &TextureBlit,
     ) {
-        let (top_left, scale) = self.get_top_left_and_scale(frame_metadata);
-
-        self.background_blitter.copy(
+        self.background_blitter.prepare_blit(
             device,
             queue,
-            encoder,
             &self
                 .image
                 .create_view(&wgpu::TextureViewDescriptor::default()),
-            background_view,
             frame_metadata,
-            top_left + offset,
-            scale,
+            blit,
         );
+        self.background_blitter.blit(encoder, background_view);
     }
 
-    pub fn get_top_left_and_scale(&self, frame_metadata: &FrameMetadata) -> (IVec2, Vec2) {
-        let scale =
-            Vec2::splat((frame_metadata.resolution().x as f32 / self.size.x as f32).max(0.5));
-        let target_size = self.size.as_vec2() * scale;
-        let top_left = -((target_size - frame_metadata.resolution().as_vec2()
-            + frame_metadata.resolution().as_vec2() * Vec2::Y)
-            * 0.5)
-            .as_ivec2();
-
-        (top_left, scale)
+    pub fn size(&self) -> UVec2 {
+        self.size
     }
 
-    pub fn get_size(&self, frame_metadata: &FrameMetadata) -> UVec2 {
-        let scale = (frame_metadata.resolution().x as f32 / self.size.x as f32).max(0.5);
-        (self.size.as_vec2() * Vec2::splat(scale)).as_uvec2()
-    }
-}
+    /// Decodes every frame of an animated WebP with more than one frame, `None` for anything
+    /// else.
+    fn decode_animation(data: &[u8]) -> Option<Vec<ImageFrame>> {
+        use image::AnimationDecoder as _;
 
-#[derive(Debug)]
-pub struct GaussianBlurPipeline {
-    render_pipeline: wgpu::RenderPipeline,
-    bind_group_layout: wgpu::BindGroupLayout,
-    sampler: wgpu::Sampler,
-    params_buffer_x: wgpu::Buffer,
-    params_buffer_y: wgpu::Buffer,
-    ping_texture: wgpu::Texture,
-    ping_view: wgpu::TextureView,
-    pong_texture: wgpu::Texture,
-    pong_view: wgpu::TextureView,
-    texture_format: wgpu::TextureFormat,
-}
+        let decoder = image::codecs::webp::WebPDecoder::new(std::io::Cursor::new(data)).ok()?;
+        if !decoder.has_animation() {
+            return None;
+        }
 
-impl GaussianBlurPipeline {
-    pub fn new(
-        device: &wgpu::Device,
-        frame_metadata: &FrameMetadata,
-        texture_format: wgpu::TextureFormat,
-    ) -> Self {
-        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Gaussian Blur Shader Module"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shader/gaussian_blur.wgsl").into()),
-        });
+        match decoder.into_frames().collect_frames() {
+            Ok(frames) if frames.len() > 1 => Some(
+                frames
+                    .into_iter()
+                    .map(|frame| {
+                        let (numer, denom) = frame.delay().numer_denom_ms();
+                        ImageFrame {
+                            duration: (numer as f32 / denom.max(1) as f32 / 1000.0)
+                                .max(MIN_FRAME_DURATION),
+                            pixels: frame.into_buffer(),
+                        }
+                    })
+                    .collect(),
+            ),
+            Ok(..) => None,
+            Err(e) => {
+                log::error!("Failed to decode animated background image: {e}");
+                None
+            }
+        }
+    }
 
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("Gaussian Blur Bind Group Layout"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        multisampled: false,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
-            ],
-        });
+    fn upload(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmap_generator: &MipmapGenerator,
+        image: &wgpu::Texture,
+        pixels: &image::RgbaImage,
+    ) {
+        queue.write_texture(
+            image.as_image_copy(),
+            pixels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(pixels.width() * 4),
+                rows_per_image: Some(pixels.height()),
+            },
+            image.size(),
+        );
 
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Gaussian Blur Pipeline Layout"),
-                bind_group_layouts: &[&bind_group_layout],
-                push_constant_ranges: &[],
-            });
+        Self::generate_mipmaps(device, queue, mipmap_generator, image);
+    }
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Gaussian Blur Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader_module,
-                entry_point: Some("vert_main"),
-                buffers: &[],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+    fn upload_bitmap(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmap_generator: &MipmapGenerator,
+        image: &wgpu::Texture,
+        bitmap: &web_sys::ImageBitmap,
+    ) {
+        queue.copy_external_image_to_texture(
+            &wgpu::CopyExternalImageSourceInfo {
+                source: wgpu::ExternalImageSource::ImageBitmap(bitmap.clone()),
+                origin: wgpu::Origin2d::ZERO,
+                flip_y: false,
             },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader_module,
-                entry_point: Some("frag_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: texture_format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
+            wgpu::CopyExternalImageDestInfo {
+                texture: image,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+                color_space: wgpu::PredefinedColorSpace::Srgb,
+                premultiplied_alpha: false,
             },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+            image.size(),
+        );
 
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("Gaussian Blur Sampler"),
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Linear,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
+        Self::generate_mipmaps(device, queue, mipmap_generator, image);
+    }
 
-        let params_buffer_x = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Gaussian Blur Params Buffer X"),
-            contents: bytemuck::bytes_of(&Vec4::new(1.0, 0.0, 0.0, 0.0)),
-            usage: wgpu::BufferUsages::UNIFORM,
+    fn generate_mipmaps(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmap_generator: &MipmapGenerator,
+        image: &wgpu::Texture,
+    ) {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Background Image Mipmap Command Encoder"),
         });
+        mipmap_generator.generate(device, &mut encoder, image);
+        queue.submit(Some(encoder.finish()));
+    }
+}
 
-        let params_buffer_y = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Gaussian Blur Params Buffer Y"),
-            contents: bytemuck::bytes_of(&Vec4::new(0.0, 1.0, 0.0, 0.0)),
-            usage: wgpu::BufferUsages::UNIFORM,
-        });
+/// Plays a muted looping video, copying its current frame into a texture every frame.
+#[derive(Debug)]
+pub struct BackgroundVideoRenderer {
+    background_blitter: TextureBlitter,
+    mipmap_generator: MipmapGenerator,
+    video: web_sys::HtmlVideoElement,
+    /// Created once the video size is known.
+    texture: Option<wgpu::Texture>,
+    /// Playback time of the frame in `texture`.
+    texture_time: Option<f64>,
+}
 
-        let (ping_texture, ping_view) = Self::create_target_texture(
-            device,
-            frame_metadata,
-            texture_format,
-            "Gaussian Blur Ping Texture",
-        );
+impl BackgroundVideoRenderer {
+    pub fn new(device: &wgpu::Device, texture_format: wgpu::TextureFormat, url: &str) -> Self {
+        let document = web_sys::window()
+            .expect_throw("window")
+            .document()
+            .expect_throw("document");
 
-        let (pong_texture, pong_view) = Self::create_target_texture(
-            device,
-            frame_metadata,
-            texture_format,
-            "Gaussian Blur Pong Texture",
-        );
+        let video = document
+            .create_element("video")
+            .unwrap_throw()
+            .dyn_into::<web_sys::HtmlVideoElement>()
+            .unwrap_throw();
+        video.set_muted(true);
+        video.set_loop(true);
+        video.set_autoplay(true);
+        video.set_cross_origin(Some("anonymous"));
+        video.set_attribute("playsinline", "").unwrap_throw();
+        video.set_src(url);
+
+        match video.play() {
+            Ok(promise) => wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = JsFuture::from(promise).await {
+                    log::error!("Failed to play background video: {e:?}");
+                }
+            }),
+            Err(e) => log::error!("Failed to play background video: {e:?}"),
+        }
+
+        let background_blitter = TextureBlitter::new(device, texture_format);
+
+        let mipmap_generator = MipmapGenerator::new(device, wgpu::TextureFormat::Rgba8Unorm);
 
         Self {
-            render_pipeline,
-            bind_group_layout,
-            sampler,
-            params_buffer_x,
-            params_buffer_y,
-            ping_texture,
-            ping_view,
-            pong_texture,
-            pong_view,
-            texture_format,
+            background_blitter,
+            mipmap_generator,
+            video,
+            texture: None,
+            texture_time: None,
         }
     }
 
-    pub fn resize(&mut self, device: &wgpu::Device, frame_metadata: &FrameMetadata) {
-        let (ping_texture, ping_view) = Self::create_target_texture(
-            device,
-            frame_metadata,
-            self.texture_format,
-            "Gaussian Blur Ping Texture",
-        );
-        let (pong_texture, pong_view) = Self::create_target_texture(
-            device,
-            frame_metadata,
-            self.texture_format,
-            "Gaussian Blur Pong Texture",
-        );
+    /// Copies the current video frame, once the video has one and only when it changed.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if self.video.ready_state() < web_sys::HtmlMediaElement::HAVE_CURRENT_DATA {
+            return;
+        }
 
-        self.ping_texture = ping_texture;
-        self.ping_view = ping_view;
-        self.pong_texture = pong_texture;
-        self.pong_view = pong_view;
-    }
+        let size = UVec2::new(self.video.video_width(), self.video.video_height());
+        if size.cmpeq(UVec2::ZERO).any() {
+            return;
+        }
 
-    pub fn blur(
-        &mut self,
-        device: &wgpu::Device,
-        _queue: &wgpu::Queue,
-        encoder: &mut wgpu::CommandEncoder,
-        input_view: &wgpu::TextureView,
-    ) {
-        self.run_pass(
-            device,
-            encoder,
-            input_view,
-            &self.ping_view,
-            Vec2::new(1.0, 0.0),
-        );
-        self.run_pass(
-            device,
-            encoder,
-            &self.ping_view,
-            &self.pong_view,
-            Vec2::new(0.0, 1.0),
+        if self
+            .texture
+            .as_ref()
+            .is_none_or(|texture| texture.width() != size.x || texture.height() != size.y)
+        {
+            self.texture = Some(device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Background Video Texture"),
+                size: wgpu::Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: mip_level_count(size.x, size.y),
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_DST
+                    | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            }));
+            self.texture_time = None;
+        }
+
+        let Some(texture) = &self.texture else {
+            return;
+        };
+
+        // A looping video jumps back, so any change of time is a new frame.
+        let time = self.video.current_time();
+        if self.texture_time.is_some() && (self.video.paused() || self.texture_time == Some(time)) {
+            return;
+        }
+        self.texture_time = Some(time);
+
+        queue.copy_external_image_to_texture(
+            &wgpu::CopyExternalImageSourceInfo {
+                source: wgpu::ExternalImageSource::HTMLVideoElement(self.video.clone()),
+                origin: wgpu::Origin2d::ZERO,
+                flip_y: false,
+            },
+            wgpu::CopyExternalImageDestInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+                color_space: wgpu::PredefinedColorSpace::Srgb,
+                premultiplied_alpha: false,
+            },
+            texture.size(),
         );
-    }
 
-    pub fn output_view(&self) -> &wgpu::TextureView {
-        &self.pong_view
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Background Video Mipmap Command Encoder"),
+        });
+        self.mipmap_generator
+            .generate(device, &mut encoder, texture);
+        queue.submit(Some(encoder.finish()));
     }
 
-    fn run_pass(
-        &self,
+    pub fn render(
+        &mut self,
         device: &wgpu::Device,
+        queue: &wgpu::Queue,
         encoder: &mut wgpu::CommandEncoder,
-        src_view: &wgpu::TextureView,
-        dst_view: &wgpu::TextureView,
-        direction: Vec2,
+        background_view: &wgpu::TextureView,
+        frame_metadata: &FrameMetadata,
+        blit: &TextureBlit,
     ) {
-        let params_buffer = if direction.x.abs() > direction.y.abs() {
-            &self.params_buffer_x
-        } else {
-            &self.params_buffer_y
+        let Some(texture) = &self.texture else {
+            return;
         };
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Gaussian Blur Bind Group"),
-            layout: &self.bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(src_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&self.sampler),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: params_buffer.as_entire_binding(),
-                },
-            ],
-        });
-
-        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Gaussian Blur Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: dst_view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                    store: wgpu::StoreOp::Store,
-                },
-                depth_slice: None,
-            })],
-            depth_stencil_attachment: None,
-            occlusion_query_set: None,
-            timestamp_writes: None,
-        });
+        self.background_blitter.prepare_blit(
+            device,
+            queue,
+            &texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            frame_metadata,
+            blit,
+        );
+        self.background_blitter.blit(encoder, background_view);
+    }
 
-        render_pass.set_pipeline(&self.render_pipeline);
-        render_pass.set_bind_group(0, &bind_group, &[]);
-        render_pass.draw(0..3, 0..1);
+    /// Size of the video, or 1x1 until its metadata is loaded.
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(self.video.video_width(), self.video.video_height()).max(UVec2::ONE)
     }
+}
 
-    fn create_target_texture(
-        device: &wgpu::Device,
-        frame_metadata: &FrameMetadata,
-        texture_format: wgpu::TextureFormat,
-        label: &str,
-    ) -> (wgpu::Texture, wgpu::TextureView) {
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some(label),
-            size: wgpu::Extent3d {
-                width: frame_metadata.resolution().x,
-                height: frame_metadata.resolution().y,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: texture_format,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_element_names() {
+        let names = SvgElement::names(
+            r#"<!DOCTYPE svg>
+            <svg xmlns="http://www.w3.org/2000/svg">
+                <g id="csharp" data-name="C#"><title>Ignored</title></g>
+                <g id="rust"><title> Rust </title></g>
+                <g id="unnamed"/>
+            </svg>"#,
+        );
 
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        (texture, view)
+        assert_eq!(names.get("csharp").map(String::as_str), Some("C#"));
+        assert_eq!(names.get("rust").map(String::as_str), Some("Rust"));
+        assert_eq!(names.get("unnamed"), None);
     }
 }
@@ -0,0 +1,493 @@
+use glam::*;
+use wgpu::util::DeviceExt as _;
+
+use crate::{frame::FrameMetadata, post_effect::PostEffect};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum PostEffectKind {
+    Bloom,
+    Vignette,
+    FilmGrain,
+    ChromaticAberration,
+}
+
+impl PostEffectKind {
+    pub fn create(
+        self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_bind_group_layout: &wgpu::BindGroupLayout,
+        meta_field_bind_group_layout: &wgpu::BindGroupLayout,
+        texture_format: wgpu::TextureFormat,
+    ) -> Box<dyn PostEffect> {
+        let layouts = PostEffectLayouts {
+            input: input_bind_group_layout,
+            meta_field: meta_field_bind_group_layout,
+        };
+
+        match self {
+            Self::Bloom => Box::new(Bloom::new(device, frame_metadata, layouts, texture_format)),
+            Self::Vignette => Box::new(Vignette::new(
+                device,
+                frame_metadata,
+                layouts,
+                texture_format,
+            )),
+            Self::FilmGrain => Box::new(FilmGrain::new(
+                device,
+                frame_metadata,
+                layouts,
+                texture_format,
+            )),
+            Self::ChromaticAberration => Box::new(ChromaticAberration::new(
+                device,
+                frame_metadata,
+                layouts,
+                texture_format,
+            )),
+        }
+    }
+}
+
+/// New parameters for one effect of the chain, see [`PostEffect::set_params`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostEffectParams {
+    Bloom(BloomParams),
+    Vignette(VignetteParams),
+    FilmGrain { strength: f32 },
+    ChromaticAberration(ChromaticAberrationParams),
+}
+
+impl PostEffectParams {
+    pub fn kind(&self) -> PostEffectKind {
+        match self {
+            Self::Bloom(_) => PostEffectKind::Bloom,
+            Self::Vignette(_) => PostEffectKind::Vignette,
+            Self::FilmGrain { .. } => PostEffectKind::FilmGrain,
+            Self::ChromaticAberration(_) => PostEffectKind::ChromaticAberration,
+        }
+    }
+}
+
+/// The bind group layouts shared by the chain, see [`PostEffect`].
+#[derive(Debug, Clone, Copy)]
+pub struct PostEffectLayouts<'a> {
+    pub input: &'a wgpu::BindGroupLayout,
+    pub meta_field: &'a wgpu::BindGroupLayout,
+}
+
+/// A full screen pipeline with the frame metadata and a params uniform at group 1.
+#[derive(Debug)]
+pub struct PostEffectPipeline {
+    render_pipeline: wgpu::RenderPipeline,
+    params_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl PostEffectPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        shader: &str,
+        frame_metadata: &FrameMetadata,
+        layouts: PostEffectLayouts,
+        params: &[u8],
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(&format!("{label} Shader Module")),
+            source: wgpu::ShaderSource::Wgsl(shader.into()),
+        });
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{label} Params Buffer")),
+            contents: params,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(&format!("{label} Bind Group Layout")),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        // The frame metadata buffer is never recreated, so neither is this.
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("{label} Bind Group")),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: frame_metadata.buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some(&format!("{label} Pipeline Layout")),
+                bind_group_layouts: &[layouts.input, &bind_group_layout, layouts.meta_field],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&format!("{label} Render Pipeline")),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vert_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("frag_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: texture_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            render_pipeline,
+            params_buffer,
+            bind_group,
+        }
+    }
+
+    pub fn update_params(&self, queue: &wgpu::Queue, params: &[u8]) {
+        queue.write_buffer(&self.params_buffer, 0, params);
+    }
+
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BloomParams {
+    /// Luminance above which a pixel glows.
+    pub threshold: f32,
+    pub intensity: f32,
+    /// Spread of the glow in pixels.
+    pub radius: f32,
+    /// Elevation at which a panel glows fully.
+    pub full_elevation: f32,
+}
+
+impl Default for BloomParams {
+    fn default() -> Self {
+        Self {
+            threshold: 0.6,
+            intensity: 0.8,
+            radius: 24.0,
+            full_elevation: 4.0,
+        }
+    }
+}
+
+/// Glow around the bright parts of elevated panels.
+#[derive(Debug)]
+pub struct Bloom {
+    pipeline: PostEffectPipeline,
+    params: BloomParams,
+}
+
+impl Bloom {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        layouts: PostEffectLayouts,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let params = BloomParams::default();
+
+        let pipeline = PostEffectPipeline::new(
+            device,
+            "Bloom Post Effect",
+            include_str!("../shader/post_bloom.wgsl"),
+            frame_metadata,
+            layouts,
+            bytemuck::bytes_of(&params),
+            texture_format,
+        );
+
+        Self { pipeline, params }
+    }
+
+    pub fn set_params(&mut self, queue: &wgpu::Queue, params: BloomParams) {
+        self.params = params;
+        self.pipeline
+            .update_params(queue, bytemuck::bytes_of(&self.params));
+    }
+}
+
+impl PostEffect for Bloom {
+    fn kind(&self) -> PostEffectKind {
+        PostEffectKind::Bloom
+    }
+
+    fn set_params(&mut self, queue: &wgpu::Queue, params: PostEffectParams) {
+        if let PostEffectParams::Bloom(params) = params {
+            Bloom::set_params(self, queue, params);
+        }
+    }
+
+    fn render(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        self.pipeline.render(render_pass);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct VignetteParams {
+    pub strength: f32,
+    /// Distance from the centre where darkening starts, relative to the half diagonal.
+    pub radius: f32,
+    pub softness: f32,
+    pub padding: f32,
+}
+
+impl Default for VignetteParams {
+    fn default() -> Self {
+        Self {
+            strength: 0.35,
+            radius: 0.6,
+            softness: 0.5,
+            padding: 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Vignette {
+    pipeline: PostEffectPipeline,
+    params: VignetteParams,
+}
+
+impl Vignette {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        layouts: PostEffectLayouts,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let params = VignetteParams::default();
+
+        let pipeline = PostEffectPipeline::new(
+            device,
+            "Vignette Post Effect",
+            include_str!("../shader/post_vignette.wgsl"),
+            frame_metadata,
+            layouts,
+            bytemuck::bytes_of(&params),
+            texture_format,
+        );
+
+        Self { pipeline, params }
+    }
+
+    pub fn set_params(&mut self, queue: &wgpu::Queue, params: VignetteParams) {
+        self.params = params;
+        self.pipeline
+            .update_params(queue, bytemuck::bytes_of(&self.params));
+    }
+}
+
+impl PostEffect for Vignette {
+    fn kind(&self) -> PostEffectKind {
+        PostEffectKind::Vignette
+    }
+
+    fn set_params(&mut self, queue: &wgpu::Queue, params: PostEffectParams) {
+        if let PostEffectParams::Vignette(params) = params {
+            Vignette::set_params(self, queue, params);
+        }
+    }
+
+    fn render(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        self.pipeline.render(render_pass);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FilmGrainParams {
+    pub strength: f32,
+    pub time: f32,
+    pub padding: Vec2,
+}
+
+impl Default for FilmGrainParams {
+    fn default() -> Self {
+        Self {
+            strength: 0.04,
+            time: 0.0,
+            padding: Vec2::ZERO,
+        }
+    }
+}
+
+/// Animated noise, reseeded every frame.
+#[derive(Debug)]
+pub struct FilmGrain {
+    pipeline: PostEffectPipeline,
+    params: FilmGrainParams,
+}
+
+impl FilmGrain {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        layouts: PostEffectLayouts,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let params = FilmGrainParams::default();
+
+        let pipeline = PostEffectPipeline::new(
+            device,
+            "Film Grain Post Effect",
+            include_str!("../shader/post_film_grain.wgsl"),
+            frame_metadata,
+            layouts,
+            bytemuck::bytes_of(&params),
+            texture_format,
+        );
+
+        Self { pipeline, params }
+    }
+
+    pub fn set_strength(&mut self, queue: &wgpu::Queue, strength: f32) {
+        self.params.strength = strength;
+        self.pipeline
+            .update_params(queue, bytemuck::bytes_of(&self.params));
+    }
+}
+
+impl PostEffect for FilmGrain {
+    fn kind(&self) -> PostEffectKind {
+        PostEffectKind::FilmGrain
+    }
+
+    fn set_params(&mut self, queue: &wgpu::Queue, params: PostEffectParams) {
+        if let PostEffectParams::FilmGrain { strength } = params {
+            self.set_strength(queue, strength);
+        }
+    }
+
+    fn update(&mut self, queue: &wgpu::Queue, delta_time: f32) {
+        // Wrapped to keep the precision of the hash.
+        self.params.time = (self.params.time + delta_time) % 1000.0;
+        self.pipeline
+            .update_params(queue, bytemuck::bytes_of(&self.params));
+    }
+
+    fn render(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        self.pipeline.render(render_pass);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ChromaticAberrationParams {
+    /// Largest channel offset in pixels, reached right at the liquid edge.
+    pub strength: f32,
+    pub padding: Vec3,
+}
+
+impl Default for ChromaticAberrationParams {
+    fn default() -> Self {
+        Self {
+            strength: 3.0,
+            padding: Vec3::ZERO,
+        }
+    }
+}
+
+/// Splits the colour channels along the field gradient around the liquid edges.
+#[derive(Debug)]
+pub struct ChromaticAberration {
+    pipeline: PostEffectPipeline,
+    params: ChromaticAberrationParams,
+}
+
+impl ChromaticAberration {
+    pub fn new(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        layouts: PostEffectLayouts,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let params = ChromaticAberrationParams::default();
+
+        let pipeline = PostEffectPipeline::new(
+            device,
+            "Chromatic Aberration Post Effect",
+            include_str!("../shader/post_chromatic_aberration.wgsl"),
+            frame_metadata,
+            layouts,
+            bytemuck::bytes_of(&params),
+            texture_format,
+        );
+
+        Self { pipeline, params }
+    }
+
+    pub fn set_params(&mut self, queue: &wgpu::Queue, params: ChromaticAberrationParams) {
+        self.params = params;
+        self.pipeline
+            .update_params(queue, bytemuck::bytes_of(&self.params));
+    }
+}
+
+impl PostEffect for ChromaticAberration {
+    fn kind(&self) -> PostEffectKind {
+        PostEffectKind::ChromaticAberration
+    }
+
+    fn set_params(&mut self, queue: &wgpu::Queue, params: PostEffectParams) {
+        if let PostEffectParams::ChromaticAberration(params) = params {
+            ChromaticAberration::set_params(self, queue, params);
+        }
+    }
+
+    fn render(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        self.pipeline.render(render_pass);
+    }
+}
@@ -0,0 +1,278 @@
+pub mod effect;
+
+use crate::{
+    frame::FrameMetadata,
+    meta_field::MetaField,
+    pipeline::{MetaFieldMag, MetaFieldRenderer},
+};
+
+pub use effect::{PostEffectKind, PostEffectParams};
+
+/// A full screen pass over the composited frame.
+///
+/// The chain binds the input frame at group 0 and the meta field at group 2 before calling
+/// [`PostEffect::render`], the effect binds its own resources from group 1.
+pub trait PostEffect: std::fmt::Debug {
+    fn kind(&self) -> PostEffectKind;
+
+    fn update(&mut self, _queue: &wgpu::Queue, _delta_time: f32) {}
+
+    /// Uploads new parameters, ignored when they belong to another kind of effect.
+    fn set_params(&mut self, queue: &wgpu::Queue, params: PostEffectParams);
+
+    fn render(&self, render_pass: &mut wgpu::RenderPass<'_>);
+}
+
+/// A ping-pong texture of the chain, the view keeps its texture alive.
+#[derive(Debug)]
+struct PostEffectTarget {
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Ordered post effects, ping-ponging between two frame sized textures.
+///
+/// The frame is composited into [`PostEffectChain::input_view`], the last enabled effect writes
+/// to the surface directly.
+#[derive(Debug)]
+pub struct PostEffectChain {
+    effects: Vec<(Box<dyn PostEffect>, bool)>,
+    input_bind_group_layout: wgpu::BindGroupLayout,
+    meta_field_bind_group_layout: wgpu::BindGroupLayout,
+    meta_field_bind_group: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+    targets: [PostEffectTarget; 2],
+    texture_format: wgpu::TextureFormat,
+}
+
+impl PostEffectChain {
+    pub fn new(
+        kinds: &[PostEffectKind],
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+        texture_format: wgpu::TextureFormat,
+    ) -> Self {
+        let input_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Post Effect Input Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let meta_field_bind_group_layout =
+            MetaFieldRenderer::<MetaFieldMag>::create_bind_group_layout(
+                device,
+                Some("Post Effect Meta Field Bind Group Layout"),
+            );
+
+        let meta_field_bind_group = MetaFieldRenderer::<MetaFieldMag>::create_bind_group(
+            device,
+            Some("Post Effect Meta Field Bind Group"),
+            &meta_field_bind_group_layout,
+            meta_field,
+        );
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Effect Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let targets = Self::create_targets(
+            device,
+            frame_metadata,
+            &input_bind_group_layout,
+            &sampler,
+            texture_format,
+        );
+
+        let effects = kinds
+            .iter()
+            .map(|kind| {
+                let effect = kind.create(
+                    device,
+                    frame_metadata,
+                    &input_bind_group_layout,
+                    &meta_field_bind_group_layout,
+                    texture_format,
+                );
+                (effect, true)
+            })
+            .collect();
+
+        Self {
+            effects,
+            input_bind_group_layout,
+            meta_field_bind_group_layout,
+            meta_field_bind_group,
+            sampler,
+            targets,
+            texture_format,
+        }
+    }
+
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        meta_field: &MetaField,
+    ) {
+        self.meta_field_bind_group = MetaFieldRenderer::<MetaFieldMag>::create_bind_group(
+            device,
+            Some("Post Effect Meta Field Bind Group"),
+            &self.meta_field_bind_group_layout,
+            meta_field,
+        );
+
+        self.targets = Self::create_targets(
+            device,
+            frame_metadata,
+            &self.input_bind_group_layout,
+            &self.sampler,
+            self.texture_format,
+        );
+    }
+
+    /// Whether any effect is enabled, otherwise the frame should go to the surface directly.
+    pub fn is_active(&self) -> bool {
+        self.effects.iter().any(|(_, enabled)| *enabled)
+    }
+
+    pub fn is_enabled(&self, kind: PostEffectKind) -> bool {
+        self.effects
+            .iter()
+            .any(|(effect, enabled)| effect.kind() == kind && *enabled)
+    }
+
+    /// Whether the effect is part of the chain at all, only those can be toggled.
+    pub fn contains(&self, kind: PostEffectKind) -> bool {
+        self.effects.iter().any(|(effect, _)| effect.kind() == kind)
+    }
+
+    pub fn set_enabled(&mut self, kind: PostEffectKind, enabled: bool) {
+        for (effect, effect_enabled) in self.effects.iter_mut() {
+            if effect.kind() == kind {
+                *effect_enabled = enabled;
+            }
+        }
+    }
+
+    pub fn set_params(&mut self, queue: &wgpu::Queue, params: PostEffectParams) {
+        for (effect, _) in self.effects.iter_mut() {
+            if effect.kind() == params.kind() {
+                effect.set_params(queue, params);
+            }
+        }
+    }
+
+    pub fn update(&mut self, queue: &wgpu::Queue, delta_time: f32) {
+        for (effect, _) in self.effects.iter_mut().filter(|(_, enabled)| *enabled) {
+            effect.update(queue, delta_time);
+        }
+    }
+
+    pub fn input_view(&self) -> &wgpu::TextureView {
+        &self.targets[0].view
+    }
+
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, surface_view: &wgpu::TextureView) {
+        let enabled = self
+            .effects
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(effect, _)| effect)
+            .collect::<Vec<_>>();
+
+        for (i, effect) in enabled.iter().enumerate() {
+            let src = &self.targets[i % 2];
+            let dst_view = match i + 1 == enabled.len() {
+                true => surface_view,
+                false => &self.targets[(i + 1) % 2].view,
+            };
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post Effect Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: dst_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            render_pass.set_bind_group(0, &src.bind_group, &[]);
+            render_pass.set_bind_group(2, &self.meta_field_bind_group, &[]);
+            effect.render(&mut render_pass);
+        }
+    }
+
+    fn create_targets(
+        device: &wgpu::Device,
+        frame_metadata: &FrameMetadata,
+        input_bind_group_layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        texture_format: wgpu::TextureFormat,
+    ) -> [PostEffectTarget; 2] {
+        ["Post Effect Ping Texture", "Post Effect Pong Texture"].map(|label| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width: frame_metadata.resolution().x,
+                    height: frame_metadata.resolution().y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: texture_format,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Post Effect Input Bind Group"),
+                layout: input_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(sampler),
+                    },
+                ],
+            });
+
+            PostEffectTarget { view, bind_group }
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+#[derive(Debug, Default)]
+struct MapState {
+    result: Option<Result<(), wgpu::BufferAsyncError>>,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+struct MapFuture(Arc<Mutex<MapState>>);
+
+impl Future for MapFuture {
+    type Output = Result<(), wgpu::BufferAsyncError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.lock().expect("map state lock");
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Copies `size` bytes of `buffer` from `offset` into a staging buffer and reads them back.
+///
+/// `buffer` must have [`wgpu::BufferUsages::COPY_SRC`].
+pub async fn read_buffer(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    buffer: &wgpu::Buffer,
+    offset: wgpu::BufferAddress,
+    size: wgpu::BufferAddress,
+) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+    if size == 0 {
+        return Ok(Vec::new());
+    }
+
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Readback Staging Buffer"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Readback Command Encoder"),
+    });
+    encoder.copy_buffer_to_buffer(buffer, offset, &staging, 0, size);
+    queue.submit(Some(encoder.finish()));
+
+    let state = Arc::new(Mutex::new(MapState::default()));
+    staging.slice(..).map_async(wgpu::MapMode::Read, {
+        let state = state.clone();
+        move |result| {
+            let mut state = state.lock().expect("map state lock");
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    });
+
+    // Blocks until the map is done on native, the browser resolves it on its own on the web.
+    if let Err(e) = device.poll(wgpu::PollType::Wait) {
+        log::error!("Failed to poll device for readback: {e}");
+    }
+
+    MapFuture(state).await?;
+
+    let bytes = staging.slice(..).get_mapped_range().to_vec();
+    staging.unmap();
+
+    Ok(bytes)
+}
+
+/// Reads the instance count of an indirect draw buffer.
+pub async fn read_instance_count(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    indirect: &wgpu::Buffer,
+) -> Result<u32, wgpu::BufferAsyncError> {
+    let bytes = read_buffer(
+        device,
+        queue,
+        indirect,
+        0,
+        std::mem::size_of::<wgpu::util::DrawIndirectArgs>() as wgpu::BufferAddress,
+    )
+    .await?;
+
+    Ok(bytemuck::pod_read_unaligned::<wgpu::util::DrawIndirectArgs>(&bytes).instance_count)
+}
+
+/// Reads the instances drawn by an indirect draw buffer, one `T` per instance.
+pub async fn read_instances<T: bytemuck::Pod>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    indirect: &wgpu::Buffer,
+    instances: &wgpu::Buffer,
+) -> Result<Vec<T>, wgpu::BufferAsyncError> {
+    let instance_count = read_instance_count(device, queue, indirect).await?;
+
+    let bytes = read_buffer(
+        device,
+        queue,
+        instances,
+        0,
+        (std::mem::size_of::<T>() * instance_count as usize) as wgpu::BufferAddress,
+    )
+    .await?;
+
+    Ok(bytemuck::pod_collect_to_vec(&bytes))
+}
+
+/// Drives a future to completion on the current thread.
+///
+/// Readbacks finish inside [`read_buffer`], so this only spins until the future is polled again.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Creates a device without a surface.
+///
+/// Returns `None` when there is no adapter that supports the full WebGPU feature set, since
+/// downlevel adapters cannot create the storage textures used by the pipelines.
+pub fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::from_env_or_default());
+    let adapter =
+        block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).ok()?;
+
+    if !adapter.get_downlevel_capabilities().is_webgpu_compliant() {
+        return None;
+    }
+
+    block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("Headless Device"),
+        required_limits: adapter.limits(),
+        ..Default::default()
+    }))
+    .ok()
+}
@@ -0,0 +1,129 @@
+struct MetaFieldMetadata {
+    offset: vec2<i32>,
+    cell_size: u32,
+}
+@group(0) @binding(0)
+var<uniform> metadata: MetaFieldMetadata;
+
+@group(0) @binding(1)
+var texture: texture_storage_2d<rg32float, read>;
+
+struct DrawIndirectArgs {
+    vertex_count: u32,
+    instance_count: atomic<u32>,
+    first_vertex: u32,
+    first_instance: u32,
+}
+@group(0) @binding(2)
+var<storage, read_write> contour_line_indirect_args: DrawIndirectArgs;
+
+struct ContourLine {
+    segment: vec4<i32>,
+    level: u32,
+}
+@group(0) @binding(3)
+var<storage, read_write> contour_lines: array<ContourLine>;
+
+override workgroup_size: u32;
+
+// 0 for magnitude, 1 for elevation.
+override level_channel: u32;
+override level_count: u32;
+override level_start: f32;
+override level_step: f32;
+
+@compute @workgroup_size(workgroup_size)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let texture_dim = textureDimensions(texture);
+    let coord = vec2<u32>(
+        id.x % texture_dim.x,
+        id.x / texture_dim.x,
+    );
+
+    if any(coord + 1u >= texture_dim) {
+        return;
+    }
+
+    let top_left_coord = coord;
+    let top_right_coord = coord + vec2<u32>(1, 0);
+    let bottom_right_coord = coord + vec2<u32>(1, 1);
+    let bottom_left_coord = coord + vec2<u32>(0, 1);
+
+    let top_left = textureLoad(texture, top_left_coord)[level_channel];
+    let top_right = textureLoad(texture, top_right_coord)[level_channel];
+    let bottom_right = textureLoad(texture, bottom_right_coord)[level_channel];
+    let bottom_left = textureLoad(texture, bottom_left_coord)[level_channel];
+
+    let top_left_pos = vec2<f32>(top_left_coord);
+    let top_right_pos = vec2<f32>(top_right_coord);
+    let bottom_right_pos = vec2<f32>(bottom_right_coord);
+    let bottom_left_pos = vec2<f32>(bottom_left_coord);
+
+    for (var level = 0u; level < level_count; level += 1u) {
+        let iso = level_start + f32(level) * level_step;
+
+        let patt = (
+            (u32(top_left >= iso) << 3u) |
+            (u32(top_right >= iso) << 2u) |
+            (u32(bottom_right >= iso) << 1u) |
+            (u32(bottom_left >= iso) << 0u)
+        );
+
+        let top_pos = lerp(top_left_pos, top_right_pos, top_left, top_right, iso);
+        let right_pos = lerp(top_right_pos, bottom_right_pos, top_right, bottom_right, iso);
+        let bottom_pos = lerp(bottom_left_pos, bottom_right_pos, bottom_left, bottom_right, iso);
+        let left_pos = lerp(top_left_pos, bottom_left_pos, top_left, bottom_left, iso);
+
+        // Saddles keep the centre inside, same as `quad_marching_squares.wgsl`.
+        switch patt {
+            case 0u, 15u, default: {
+            }
+            case 1u, 14u: {
+                push_line(left_pos, bottom_pos, level);
+            }
+            case 2u, 13u: {
+                push_line(bottom_pos, right_pos, level);
+            }
+            case 3u, 12u: {
+                push_line(left_pos, right_pos, level);
+            }
+            case 4u, 11u: {
+                push_line(top_pos, right_pos, level);
+            }
+            case 6u, 9u: {
+                push_line(top_pos, bottom_pos, level);
+            }
+            case 7u, 8u: {
+                push_line(top_pos, left_pos, level);
+            }
+            case 5u: {
+                push_line(top_pos, left_pos, level);
+                push_line(bottom_pos, right_pos, level);
+            }
+            case 10u: {
+                push_line(top_pos, right_pos, level);
+                push_line(left_pos, bottom_pos, level);
+            }
+        }
+    }
+}
+
+fn push_line(start: vec2<f32>, end: vec2<f32>, level: u32) {
+    let index = atomicAdd(&contour_line_indirect_args.instance_count, 1u);
+    contour_lines[index] = ContourLine(
+        vec4<i32>(
+            vec2<i32>(start * f32(metadata.cell_size)) + metadata.offset,
+            vec2<i32>(end * f32(metadata.cell_size)) + metadata.offset,
+        ),
+        level,
+    );
+}
+
+fn lerp(p1: vec2<f32>, p2: vec2<f32>, v1: f32, v2: f32, iso: f32) -> vec2<f32> {
+    if v1 == v2 {
+        return (p1 + p2) * 0.5;
+    }
+
+    let t = (iso - v1) / (v2 - v1);
+    return p1 + t * (p2 - p1);
+}
@@ -0,0 +1,64 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var input_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var input_sampler: sampler;
+
+struct BlurParams {
+    offset: f32,
+}
+@group(0) @binding(2)
+var<uniform> params: BlurParams;
+
+@vertex
+fn vert_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(
+        f32((vertex_index << 1u) & 2u),
+        f32(vertex_index & 2u),
+    );
+    let pos = uv * 2.0 - 1.0;
+
+    return VertexOutput(
+        vec4<f32>(pos, 0.0, 1.0),
+        uv,
+    );
+}
+
+@fragment
+fn frag_down(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let half_texel = 0.5 / vec2<f32>(textureDimensions(input_texture)) * params.offset;
+
+    var color = sample(uv) * 4.0;
+    color += sample(uv - half_texel);
+    color += sample(uv + half_texel);
+    color += sample(uv + vec2<f32>(half_texel.x, -half_texel.y));
+    color += sample(uv - vec2<f32>(half_texel.x, -half_texel.y));
+
+    return vec4<f32>(color / 8.0, 1.0);
+}
+
+@fragment
+fn frag_up(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let half_texel = 0.5 / vec2<f32>(textureDimensions(input_texture)) * params.offset;
+
+    var color = sample(uv + vec2<f32>(-half_texel.x * 2.0, 0.0));
+    color += sample(uv + vec2<f32>(-half_texel.x, half_texel.y)) * 2.0;
+    color += sample(uv + vec2<f32>(0.0, half_texel.y * 2.0));
+    color += sample(uv + vec2<f32>(half_texel.x, half_texel.y)) * 2.0;
+    color += sample(uv + vec2<f32>(half_texel.x * 2.0, 0.0));
+    color += sample(uv + vec2<f32>(half_texel.x, -half_texel.y)) * 2.0;
+    color += sample(uv + vec2<f32>(0.0, -half_texel.y * 2.0));
+    color += sample(uv + vec2<f32>(-half_texel.x, -half_texel.y)) * 2.0;
+
+    return vec4<f32>(color / 12.0, 1.0);
+}
+
+fn sample(uv: vec2<f32>) -> vec3<f32> {
+    let clamped_uv = clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0));
+    return textureSampleLevel(input_texture, input_sampler, clamped_uv, 0.0).rgb;
+}
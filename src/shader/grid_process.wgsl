@@ -20,6 +20,42 @@ var<storage, read_write> pos: array<vec2<f32>>;
 @group(0) @binding(5)
 var<storage, read_write> vel: array<vec2<f32>>;
 
+struct GridSpring {
+    stiffness: f32,
+    damping: f32,
+}
+@group(0) @binding(6)
+var<uniform> spring: GridSpring;
+
+struct MetaBall {
+    center: vec2<f32>,
+    radius: f32,
+    hidden: u32,
+}
+
+struct MetaBox {
+    min: vec2<f32>,
+    max: vec2<f32>,
+    elevation: f32,
+}
+
+@group(0) @binding(7)
+var<storage> balls: array<MetaBall>;
+
+@group(0) @binding(8)
+var<storage> boxes: array<MetaBox>;
+
+struct Impulse {
+    position: vec2<f32>,
+    strength: f32,
+    radius: f32,
+    decay: f32,
+    age: f32,
+    _padding: vec2<f32>,
+}
+@group(0) @binding(9)
+var<storage> impulses: array<Impulse>;
+
 struct VertexOutput {
     @location(0) @interpolate(flat) radius: f32,
     @location(1) local_pixel_pos: vec2<f32>,
@@ -28,6 +64,12 @@ struct VertexOutput {
 
 override cell_size: u32;
 override workgroup_size: u32;
+override base_radius: f32;
+override impulse_speed: f32;
+
+// How far from the liquid edge the shapes push the grid, and how hard per unit of elevation.
+const shape_influence_radius: f32 = 64.0;
+const shape_repel_strength: f32 = 60.0;
 
 fn clamp_length(v: vec2<f32>, max_len: f32) -> vec2<f32> {
     let len_sq = dot(v, v);
@@ -65,18 +107,21 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     let softening = 150.0;
     let influence_radius = 150.0 * select(1.0, 1.2, is_outer);
     let repel_strength = 15000.0 * select(1.0, 1.2, is_outer);
-    let spring_k = 20.0 * select(1.0, 1.2, is_outer);
-    let damping = 4.0 * select(1.0, 1.0 / 1.2, is_outer);
+    let spring_k = spring.stiffness * select(1.0, 1.2, is_outer);
+    let damping = spring.damping * select(1.0, 1.0 / 1.2, is_outer);
 
     let inv_r2 = 1.0 / (dist_sq + softening * softening);
     let dir = select(vec2<f32>(0.0), disp_from_target / max(dist, 1e-3), dist > 1e-3);
     let fade = 1.0 - smoothstep(0.0, influence_radius, dist);
 
     let a_repel = dir * (repel_strength * repel_strength * inv_r2) * fade;
+    let page_pos = current_pixel_pos + vec2<f32>(frame_metadata.top_left);
+    let a_shape = shape_repel(page_pos) * select(1.0, 1.2, is_outer);
+    let a_impulse = impulse_push(page_pos) * select(1.0, 1.2, is_outer);
     let a_spring = -spring_k * p;
     let a_damp = -damping * v;
 
-    var a = a_repel + a_spring + a_damp;
+    var a = a_repel + a_shape + a_impulse + a_spring + a_damp;
 
     let max_accel = 250000.0;
     a = clamp_length(a, max_accel);
@@ -88,4 +133,76 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
 
     pos[index] = p;
     vel[index] = v;
-}
\ No newline at end of file
+}
+
+// Outward push of every wavefront passing through `page_pos`, see `impulse.rs`.
+fn impulse_push(page_pos: vec2<f32>) -> vec2<f32> {
+    var a = vec2<f32>(0.0);
+
+    for (var i = 0u; i < arrayLength(&impulses); i += 1u) {
+        let impulse = impulses[i];
+        if impulse.strength == 0.0 || impulse.radius <= 0.0 {
+            continue;
+        }
+
+        let disp = page_pos - impulse.position;
+        let dist = length(disp);
+        if dist < 1e-3 {
+            continue;
+        }
+
+        let front = impulse.age * impulse_speed;
+        let band = 1.0 - smoothstep(0.0, impulse.radius, abs(dist - front));
+        a += disp / dist * impulse.strength * exp(-impulse.decay * impulse.age) * band;
+    }
+
+    return a;
+}
+
+// Pushes away from the liquid edge of every shape, harder for elevated panels.
+fn shape_repel(page_pos: vec2<f32>) -> vec2<f32> {
+    var a = vec2<f32>(0.0);
+
+    for (var i = 0u; i < arrayLength(&balls); i += 1u) {
+        let ball = balls[i];
+        if ball.hidden == 1u {
+            continue;
+        }
+
+        let disp = page_pos - ball.center;
+        let dist = length(disp);
+        let normal = select(vec2<f32>(0.0), disp / dist, dist > 1e-3);
+        a += edge_repel(dist - ball.radius - base_radius, normal, 0.0);
+    }
+
+    for (var i = 0u; i < arrayLength(&boxes); i += 1u) {
+        let box = boxes[i];
+        let center = (box.min + box.max) * 0.5;
+        let half_size = (box.max - box.min) * 0.5;
+        let disp = page_pos - center;
+        let q = abs(disp) - half_size;
+
+        let outside = length(max(q, vec2<f32>(0.0)));
+        let inside = min(max(q.x, q.y), 0.0);
+
+        var normal: vec2<f32>;
+        if outside > 0.0 {
+            normal = sign(disp) * max(q, vec2<f32>(0.0)) / outside;
+        } else if q.x > q.y {
+            normal = vec2<f32>(sign(disp.x), 0.0);
+        } else {
+            normal = vec2<f32>(0.0, sign(disp.y));
+        }
+
+        a += edge_repel(outside + inside - base_radius, normal, box.elevation);
+    }
+
+    return a;
+}
+
+// `dist` is signed from the edge along the outward `normal`, points on both sides move away from it.
+fn edge_repel(dist: f32, normal: vec2<f32>, elevation: f32) -> vec2<f32> {
+    let fade = 1.0 - smoothstep(0.0, shape_influence_radius, abs(dist));
+    let side = select(-1.0, 1.0, dist >= 0.0);
+    return normal * side * shape_repel_strength * (1.0 + elevation) * fade;
+}
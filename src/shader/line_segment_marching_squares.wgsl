@@ -30,6 +30,10 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         id.x / texture_dim.x,
     );
 
+    if any(coord + 1u >= texture_dim) {
+        return;
+    }
+
     let top_left_coord = coord;
     let top_right_coord = coord + vec2<u32>(1, 0);
     let bottom_right_coord = coord + vec2<u32>(1, 1);
@@ -41,85 +45,68 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     let bottom_left = textureLoad(texture, bottom_left_coord).x;
 
     let patt = (
-        (u32(top_left > 1.0) << 3u) |
-        (u32(top_right > 1.0) << 2u) |
-        (u32(bottom_right > 1.0) << 1u) |
-        (u32(bottom_left > 1.0) << 0u)
+        (u32(top_left >= 1.0) << 3u) |
+        (u32(top_right >= 1.0) << 2u) |
+        (u32(bottom_right >= 1.0) << 1u) |
+        (u32(bottom_left >= 1.0) << 0u)
     );
 
-    if patt == 0u || patt == 15u {
-        return;
-    }
-
     let top_left_pos = vec2<f32>(top_left_coord);
     let top_right_pos = vec2<f32>(top_right_coord);
     let bottom_right_pos = vec2<f32>(bottom_right_coord);
     let bottom_left_pos = vec2<f32>(bottom_left_coord);
 
-    var index = 0u;
-    var new_line_segment_indices: array<u32, 2>;
-    var new_line_segments: array<vec2<f32>, 4>;
+    let top_pos = lerp(top_left_pos, top_right_pos, top_left, top_right);
+    let right_pos = lerp(top_right_pos, bottom_right_pos, top_right, bottom_right);
+    let bottom_pos = lerp(bottom_left_pos, bottom_right_pos, bottom_left, bottom_right);
+    let left_pos = lerp(top_left_pos, bottom_left_pos, top_left, bottom_left);
 
-    // Top
-    if patt >= 4u && patt <= 11u {
-        let p = lerp(top_left_pos, top_right_pos, top_left, top_right);
-        new_line_segments[index] = p;
-
-        if index == 0u {
-            new_line_segment_indices[0] = atomicAdd(&line_segment_indirect_args.instance_count, 1u);
+    // Saddles keep the centre inside, same as `quad_marching_squares.wgsl`.
+    switch patt {
+        case 0u, 15u, default: {
         }
-
-        index += 1;
-    }
-
-    // Left
-    if ((patt >> 3u) & 1u) != (patt & 1u) {
-        let p = lerp(top_left_pos, bottom_left_pos, top_left, bottom_left);
-        new_line_segments[index] = p;
-
-        if index == 0u {
-            new_line_segment_indices[0] = atomicAdd(&line_segment_indirect_args.instance_count, 1u);
+        case 1u, 14u: {
+            push_line_segment(left_pos, bottom_pos);
         }
-
-        index += 1;
-    }
-
-    // Bottom
-    if (patt & 1u) != ((patt >> 1u) & 1u) {
-        let p = lerp(bottom_left_pos, bottom_right_pos, bottom_left, bottom_right);
-        new_line_segments[index] = p;
-
-        if index == 0u {
-            new_line_segment_indices[0] = atomicAdd(&line_segment_indirect_args.instance_count, 1u);
-        } else if index == 2u {
-            new_line_segment_indices[1] = atomicAdd(&line_segment_indirect_args.instance_count, 1u);
+        case 2u, 13u: {
+            push_line_segment(bottom_pos, right_pos);
+        }
+        case 3u, 12u: {
+            push_line_segment(left_pos, right_pos);
+        }
+        case 4u, 11u: {
+            push_line_segment(top_pos, right_pos);
+        }
+        case 6u, 9u: {
+            push_line_segment(top_pos, bottom_pos);
+        }
+        case 7u, 8u: {
+            push_line_segment(top_pos, left_pos);
+        }
+        case 5u: {
+            push_line_segment(top_pos, left_pos);
+            push_line_segment(bottom_pos, right_pos);
+        }
+        case 10u: {
+            push_line_segment(top_pos, right_pos);
+            push_line_segment(left_pos, bottom_pos);
         }
-
-        index += 1;
-    }
-
-    // Right
-    if ((patt >> 1u) & 1u) != ((patt >> 2u) & 1u) {
-        let p = lerp(top_right_pos, bottom_right_pos, top_right, bottom_right);
-        new_line_segments[index] = p;
-
-        index += 1;
     }
+}
 
-    line_segments[new_line_segment_indices[0]] = vec4<i32>(
-        vec2<i32>(new_line_segments[0] * f32(metadata.cell_size)) + metadata.offset,
-        vec2<i32>(new_line_segments[1] * f32(metadata.cell_size)) + metadata.offset,
+fn push_line_segment(start: vec2<f32>, end: vec2<f32>) {
+    let index = atomicAdd(&line_segment_indirect_args.instance_count, 1u);
+    line_segments[index] = vec4<i32>(
+        vec2<i32>(start * f32(metadata.cell_size)) + metadata.offset,
+        vec2<i32>(end * f32(metadata.cell_size)) + metadata.offset,
     );
-
-    if index == 4u {
-        line_segments[new_line_segment_indices[1]] = vec4<i32>(
-            vec2<i32>(new_line_segments[2] * f32(metadata.cell_size)) + metadata.offset,
-            vec2<i32>(new_line_segments[3] * f32(metadata.cell_size)) + metadata.offset,
-        );
-    }
 }
 
 fn lerp(p1: vec2<f32>, p2: vec2<f32>, v1: f32, v2: f32) -> vec2<f32> {
+    if v1 == v2 {
+        return (p1 + p2) * 0.5;
+    }
+
     let t = (1.0 - v1) / (v2 - v1);
     return p1 + t * (p2 - p1);
-}
\ No newline at end of file
+}
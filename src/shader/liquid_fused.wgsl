@@ -0,0 +1,72 @@
+@vertex
+fn vert_main(@builtin(vertex_index) vert_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(
+        f32((vert_index << 1u) & 2u),
+        f32(vert_index & 2u),
+    );
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn frag_main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    if !is_inside_cell_polygon(frag_coord.xy) {
+        discard;
+    }
+
+    return vec4<f32>(shade_liquid(frag_coord), 1.0);
+}
+
+// Classifies the pixel against the marching squares polygon of its field cell, the same area
+// `quad_marching_squares.wgsl` would cover with quads.
+fn is_inside_cell_polygon(pixel_pos: vec2<f32>) -> bool {
+    let cell_pos = (pixel_pos - vec2<f32>(metadata.offset)) / f32(metadata.cell_size);
+    let cell = vec2<i32>(floor(cell_pos));
+
+    let corners = array<vec2<f32>, 4>(
+        vec2<f32>(cell),
+        vec2<f32>(cell + vec2<i32>(0, 1)),
+        vec2<f32>(cell + vec2<i32>(1, 1)),
+        vec2<f32>(cell + vec2<i32>(1, 0)),
+    );
+    let values = array<f32, 4>(
+        load_meta_mag(cell).x,
+        load_meta_mag(cell + vec2<i32>(0, 1)).x,
+        load_meta_mag(cell + vec2<i32>(1, 1)).x,
+        load_meta_mag(cell + vec2<i32>(1, 0)).x,
+    );
+
+    // Walk the cell boundary down the left edge first, keeping the inside corners and the edge
+    // crossings, which is always a convex polygon with saddles keeping the centre inside.
+    var polygon: array<vec2<f32>, 8>;
+    var count = 0u;
+
+    for (var i = 0u; i < 4u; i += 1u) {
+        let next = (i + 1u) % 4u;
+        let inside = values[i] >= 1.0;
+
+        if inside {
+            polygon[count] = corners[i];
+            count += 1u;
+        }
+
+        if inside != (values[next] >= 1.0) {
+            let t = (1.0 - values[i]) / (values[next] - values[i]);
+            polygon[count] = mix(corners[i], corners[next], t);
+            count += 1u;
+        }
+    }
+
+    if count < 3u {
+        return false;
+    }
+
+    for (var i = 0u; i < count; i += 1u) {
+        let edge = polygon[(i + 1u) % count] - polygon[i];
+        let to_pos = cell_pos - polygon[i];
+        if edge.x * to_pos.y - edge.y * to_pos.x > 0.0 {
+            return false;
+        }
+    }
+
+    return true;
+}
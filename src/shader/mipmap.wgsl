@@ -0,0 +1,29 @@
+@group(0) @binding(0)
+var src_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var src_sampler: sampler;
+
+struct VertexOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vert_main(@builtin(vertex_index) vertex_index: u32) -> VertexOut {
+    let uv = vec2<f32>(
+        f32((vertex_index << 1u) & 2u),
+        f32(vertex_index & 2u),
+    );
+
+    var out: VertexOut;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+// A bilinear sample halfway between four texels is their box filtered average.
+@fragment
+fn frag_main(in: VertexOut) -> @location(0) vec4<f32> {
+    return textureSampleLevel(src_texture, src_sampler, in.uv, 0.0);
+}
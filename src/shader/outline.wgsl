@@ -0,0 +1,48 @@
+struct FrameMetadata {
+    resolution: vec2<u32>,
+    top_left: vec2<i32>,
+}
+@group(0) @binding(0)
+var<uniform> frame_metadata: FrameMetadata;
+
+@group(0) @binding(1)
+var<storage> line_segments: array<vec4<i32>>;
+
+struct OutlineStyle {
+    color: vec4<f32>,
+    width: f32,
+}
+@group(1) @binding(0)
+var<uniform> style: OutlineStyle;
+
+struct VertexOutput {
+    @location(0) @interpolate(flat) segment: vec4<i32>,
+    @builtin(position) position: vec4<f32>,
+}
+
+@vertex
+fn vert_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    let segment = line_segments[instance_index];
+
+    let pixel_pos = expand_segment(vec2<f32>(segment.xy), vec2<f32>(segment.zw), style.width * 0.5 + 1.0, vertex_index);
+    let clip_pos = (pixel_pos / vec2<f32>(frame_metadata.resolution)) * 2.0 - 1.0;
+
+    return VertexOutput(
+        segment,
+        vec4<f32>(clip_pos.x, -clip_pos.y, 0.0, 1.0),
+    );
+}
+
+@fragment
+fn frag_main(
+    @location(0) @interpolate(flat) segment: vec4<i32>,
+    @builtin(position) frag_coord: vec4<f32>,
+) -> @location(0) vec4<f32> {
+    let half_width = style.width * 0.5;
+    let dist = distance_to_segment(frag_coord.xy, vec2<f32>(segment.xy), vec2<f32>(segment.zw));
+    let coverage = 1.0 - smoothstep(half_width - 0.5, half_width + 0.5, dist);
+    return vec4<f32>(style.color.rgb, style.color.a * coverage);
+}
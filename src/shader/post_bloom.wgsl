@@ -0,0 +1,91 @@
+@group(0) @binding(0)
+var input_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var input_sampler: sampler;
+
+struct FrameMetadata {
+    resolution: vec2<u32>,
+    top_left: vec2<i32>,
+}
+@group(1) @binding(0)
+var<uniform> frame_metadata: FrameMetadata;
+
+struct BloomParams {
+    threshold: f32,
+    intensity: f32,
+    radius: f32,
+    full_elevation: f32,
+}
+@group(1) @binding(1)
+var<uniform> params: BloomParams;
+
+struct MetaFieldMetadata {
+    offset: vec2<i32>,
+    cell_size: u32,
+}
+@group(2) @binding(0)
+var<uniform> metadata: MetaFieldMetadata;
+
+@group(2) @binding(1)
+var meta_field_texture: texture_2d<f32>;
+
+@vertex
+fn vert_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(
+        f32((vertex_index << 1u) & 2u),
+        f32(vertex_index & 2u),
+    );
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn sample_input(frag_coord: vec2<f32>) -> vec3<f32> {
+    let uv = saturate(frag_coord / vec2<f32>(frame_metadata.resolution));
+    return textureSampleLevel(input_texture, input_sampler, uv, 0.0).rgb;
+}
+
+fn load_meta(frag_coord: vec2<f32>) -> vec2<f32> {
+    let texture_dim = vec2<i32>(textureDimensions(meta_field_texture));
+    let coord = vec2<i32>(floor((frag_coord - vec2<f32>(metadata.offset)) / f32(metadata.cell_size)));
+    return textureLoad(meta_field_texture, clamp(coord, vec2<i32>(0), texture_dim - 1), 0).xy;
+}
+
+const ring_count: i32 = 3;
+const ring_taps: i32 = 8;
+const tau: f32 = 6.28318530718;
+
+@fragment
+fn frag_main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    let color = sample_input(frag_coord.xy);
+
+    var glow = vec3<f32>(0.0);
+    var total = 0.0;
+
+    for (var ring = 1; ring <= ring_count; ring += 1) {
+        let ring_radius = params.radius * f32(ring) / f32(ring_count);
+        let weight = 1.0 - f32(ring - 1) / f32(ring_count);
+
+        for (var tap = 0; tap < ring_taps; tap += 1) {
+            // Rotate every other ring by half a step to break up the pattern.
+            let angle = (f32(tap) + 0.5 * f32(ring % 2)) / f32(ring_taps) * tau;
+            let pos = frag_coord.xy + vec2<f32>(cos(angle), sin(angle)) * ring_radius;
+
+            glow += bright(pos) * weight;
+            total += weight;
+        }
+    }
+
+    return vec4<f32>(color + glow / total * params.intensity, 1.0);
+}
+
+// The part of the colour above the threshold, only on elevated panels.
+fn bright(pos: vec2<f32>) -> vec3<f32> {
+    let elevation = load_meta(pos).y;
+    let mask = smoothstep(0.0, params.full_elevation, elevation);
+
+    let color = sample_input(pos);
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let excess = max(luminance - params.threshold, 0.0) / max(luminance, 1e-4);
+
+    return color * excess * mask;
+}
@@ -0,0 +1,75 @@
+@group(0) @binding(0)
+var input_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var input_sampler: sampler;
+
+struct FrameMetadata {
+    resolution: vec2<u32>,
+    top_left: vec2<i32>,
+}
+@group(1) @binding(0)
+var<uniform> frame_metadata: FrameMetadata;
+
+struct ChromaticAberrationParams {
+    strength: f32,
+}
+@group(1) @binding(1)
+var<uniform> params: ChromaticAberrationParams;
+
+struct MetaFieldMetadata {
+    offset: vec2<i32>,
+    cell_size: u32,
+}
+@group(2) @binding(0)
+var<uniform> metadata: MetaFieldMetadata;
+
+@group(2) @binding(1)
+var meta_field_texture: texture_2d<f32>;
+
+@vertex
+fn vert_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(
+        f32((vertex_index << 1u) & 2u),
+        f32(vertex_index & 2u),
+    );
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn sample_input(frag_coord: vec2<f32>) -> vec3<f32> {
+    let uv = saturate(frag_coord / vec2<f32>(frame_metadata.resolution));
+    return textureSampleLevel(input_texture, input_sampler, uv, 0.0).rgb;
+}
+
+fn load_meta(frag_coord: vec2<f32>) -> vec2<f32> {
+    let texture_dim = vec2<i32>(textureDimensions(meta_field_texture));
+    let coord = vec2<i32>(floor((frag_coord - vec2<f32>(metadata.offset)) / f32(metadata.cell_size)));
+    return textureLoad(meta_field_texture, clamp(coord, vec2<i32>(0), texture_dim - 1), 0).xy;
+}
+
+@fragment
+fn frag_main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    let cell_size = f32(metadata.cell_size);
+    let mag = min(load_meta(frag_coord.xy).x, 2.0);
+
+    // Strongest right at the iso level of 1, fading out on both sides of the edge.
+    let edge = 1.0 - smoothstep(0.0, 0.5, abs(mag - 1.0));
+    if edge <= 0.0 {
+        return vec4<f32>(sample_input(frag_coord.xy), 1.0);
+    }
+
+    let grad = vec2<f32>(
+        min(load_meta(frag_coord.xy + vec2<f32>(cell_size, 0.0)).x, 2.0)
+            - min(load_meta(frag_coord.xy - vec2<f32>(cell_size, 0.0)).x, 2.0),
+        min(load_meta(frag_coord.xy + vec2<f32>(0.0, cell_size)).x, 2.0)
+            - min(load_meta(frag_coord.xy - vec2<f32>(0.0, cell_size)).x, 2.0),
+    );
+    let dir = select(vec2<f32>(0.0), normalize(grad), length(grad) > 1e-4);
+    let offset = dir * params.strength * edge;
+
+    let r = sample_input(frag_coord.xy + offset).r;
+    let g = sample_input(frag_coord.xy).g;
+    let b = sample_input(frag_coord.xy - offset).b;
+
+    return vec4<f32>(r, g, b, 1.0);
+}
@@ -0,0 +1,45 @@
+@group(0) @binding(0)
+var input_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var input_sampler: sampler;
+
+struct FrameMetadata {
+    resolution: vec2<u32>,
+    top_left: vec2<i32>,
+}
+@group(1) @binding(0)
+var<uniform> frame_metadata: FrameMetadata;
+
+struct VignetteParams {
+    strength: f32,
+    radius: f32,
+    softness: f32,
+}
+@group(1) @binding(1)
+var<uniform> params: VignetteParams;
+
+@vertex
+fn vert_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(
+        f32((vertex_index << 1u) & 2u),
+        f32(vertex_index & 2u),
+    );
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn sample_input(frag_coord: vec2<f32>) -> vec3<f32> {
+    let uv = saturate(frag_coord / vec2<f32>(frame_metadata.resolution));
+    return textureSampleLevel(input_texture, input_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn frag_main(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    let color = sample_input(frag_coord.xy);
+
+    let resolution = vec2<f32>(frame_metadata.resolution);
+    let dist = length(frag_coord.xy - resolution * 0.5) / length(resolution * 0.5);
+    let shade = smoothstep(params.radius, params.radius + params.softness, dist);
+
+    return vec4<f32>(color * (1.0 - shade * params.strength), 1.0);
+}
@@ -0,0 +1,29 @@
+// Expands a segment into a quad covering it with `extent` pixels of margin.
+fn expand_segment(start: vec2<f32>, end: vec2<f32>, extent: f32, vertex_index: u32) -> vec2<f32> {
+    let line = end - start;
+    var dir = vec2<f32>(extent, 0.0);
+    if dot(line, line) > 0.0 {
+        dir = normalize(line) * extent;
+    }
+    let perp = vec2<f32>(-dir.y, dir.x);
+
+    let positions = array<vec2<f32>, 4>(
+        start - perp - dir,
+        start + perp - dir,
+        end - perp + dir,
+        end + perp + dir,
+    );
+
+    return positions[vertex_index];
+}
+
+fn distance_to_segment(p: vec2<f32>, start: vec2<f32>, end: vec2<f32>) -> f32 {
+    let line = end - start;
+    let len_sq = dot(line, line);
+    if len_sq == 0.0 {
+        return distance(p, start);
+    }
+
+    let t = clamp(dot(p - start, line) / len_sq, 0.0, 1.0);
+    return distance(p, start + t * line);
+}
@@ -4,8 +4,12 @@ struct FrameMetadata {
 };
 
 struct TextureBlitParams {
-    position: vec2<f32>,
-    scale: vec2<f32>,
+    center: vec2<f32>,
+    half_size: vec2<f32>,
+    uv_offset: vec2<f32>,
+    uv_scale: vec2<f32>,
+    rotation: f32,
+    opacity: f32,
 };
 
 struct VertexOut {
@@ -27,28 +31,27 @@ var<uniform> frame: FrameMetadata;
 
 @vertex
 fn vert_main(@builtin(vertex_index) vertex_index: u32) -> VertexOut {
-    let src_size = vec2<f32>(textureDimensions(src_texture));
     let dst_size = vec2<f32>(frame.resolution);
-    let pos = params.position;
 
-    let tl = pos;
-    let br = pos + src_size * params.scale;
-
-    var positions = array<vec2<f32>, 4>(
-        vec2<f32>(tl.x, tl.y),
-        vec2<f32>(br.x, tl.y),
-        vec2<f32>(tl.x, br.y),
-        vec2<f32>(br.x, br.y)
-    );
-
-    var uvs = array<vec2<f32>, 4>(
+    var corners = array<vec2<f32>, 4>(
         vec2<f32>(0.0, 0.0),
         vec2<f32>(1.0, 0.0),
         vec2<f32>(0.0, 1.0),
         vec2<f32>(1.0, 1.0)
     );
 
-    let pos_px = positions[vertex_index];
+    let corner = corners[vertex_index];
+    let local = (corner * 2.0 - 1.0) * params.half_size;
+
+    // Clockwise on screen since y points down.
+    let cos_r = cos(params.rotation);
+    let sin_r = sin(params.rotation);
+    let rotated = vec2<f32>(
+        local.x * cos_r - local.y * sin_r,
+        local.x * sin_r + local.y * cos_r
+    );
+
+    let pos_px = params.center + rotated;
     let ndc = vec2<f32>(
         (pos_px.x / dst_size.x) * 2.0 - 1.0,
         1.0 - (pos_px.y / dst_size.y) * 2.0
@@ -56,11 +59,14 @@ fn vert_main(@builtin(vertex_index) vertex_index: u32) -> VertexOut {
 
     var out: VertexOut;
     out.position = vec4<f32>(ndc, 0.0, 1.0);
-    out.uv = uvs[vertex_index];
+    out.uv = params.uv_offset + corner * params.uv_scale;
     return out;
 }
 
+// Outputs premultiplied alpha, the blend state decides how it is combined.
 @fragment
 fn frag_main(in: VertexOut) -> @location(0) vec4<f32> {
-    return textureSample(src_texture, src_sampler, in.uv);
+    let color = textureSample(src_texture, src_sampler, in.uv);
+    let alpha = color.a * params.opacity;
+    return vec4<f32>(color.rgb * alpha, alpha);
 }
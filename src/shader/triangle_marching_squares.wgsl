@@ -0,0 +1,98 @@
+struct MetaFieldMetadata {
+    offset: vec2<i32>,
+    cell_size: u32,
+}
+@group(0) @binding(0)
+var<uniform> metadata: MetaFieldMetadata;
+
+@group(0) @binding(1)
+var texture: texture_storage_2d<rg32float, read>;
+
+struct DrawIndirectArgs {
+    vertex_count: u32,
+    instance_count: atomic<u32>,
+    first_vertex: u32,
+    first_instance: u32,
+}
+@group(0) @binding(2)
+var<storage, read_write> triangle_indirect_args: DrawIndirectArgs;
+
+@group(0) @binding(3)
+var<storage, read_write> triangles: array<vec2<f32>>;
+
+override workgroup_size: u32;
+
+@compute @workgroup_size(workgroup_size)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let texture_dim = textureDimensions(texture);
+    let coord = vec2<u32>(
+        id.x % texture_dim.x,
+        id.x / texture_dim.x,
+    );
+
+    if any(coord + 1u >= texture_dim) {
+        return;
+    }
+
+    let top_left_coord = coord;
+    let top_right_coord = coord + vec2<u32>(1, 0);
+    let bottom_right_coord = coord + vec2<u32>(1, 1);
+    let bottom_left_coord = coord + vec2<u32>(0, 1);
+
+    let top_left = textureLoad(texture, top_left_coord).x;
+    let top_right = textureLoad(texture, top_right_coord).x;
+    let bottom_right = textureLoad(texture, bottom_right_coord).x;
+    let bottom_left = textureLoad(texture, bottom_left_coord).x;
+
+    let top_left_pos = vec2<f32>(top_left_coord);
+    let top_right_pos = vec2<f32>(top_right_coord);
+    let bottom_right_pos = vec2<f32>(bottom_right_coord);
+    let bottom_left_pos = vec2<f32>(bottom_left_coord);
+
+    // Walk the cell boundary from the top left corner down the left edge, keeping the inside
+    // corners and the edge crossings. The result is always a convex polygon, saddles included
+    // since they keep the centre inside like `quad_marching_squares.wgsl`.
+    let corners = array<vec2<f32>, 4>(top_left_pos, bottom_left_pos, bottom_right_pos, top_right_pos);
+    let values = array<f32, 4>(top_left, bottom_left, bottom_right, top_right);
+
+    var polygon: array<vec2<f32>, 8>;
+    var count = 0u;
+
+    for (var i = 0u; i < 4u; i += 1u) {
+        let next = (i + 1u) % 4u;
+        let inside = values[i] >= 1.0;
+
+        if inside {
+            polygon[count] = corners[i];
+            count += 1u;
+        }
+
+        if inside != (values[next] >= 1.0) {
+            polygon[count] = lerp(corners[i], corners[next], values[i], values[next]);
+            count += 1u;
+        }
+    }
+
+    if count < 3u {
+        return;
+    }
+
+    let triangle_count = count - 2u;
+    let index = atomicAdd(&triangle_indirect_args.instance_count, triangle_count);
+
+    for (var i = 0u; i < triangle_count; i += 1u) {
+        let base = (index + i) * 3u;
+        triangles[base + 0u] = to_pixel(polygon[0]);
+        triangles[base + 1u] = to_pixel(polygon[i + 1u]);
+        triangles[base + 2u] = to_pixel(polygon[i + 2u]);
+    }
+}
+
+fn to_pixel(pos: vec2<f32>) -> vec2<f32> {
+    return pos * f32(metadata.cell_size) + vec2<f32>(metadata.offset);
+}
+
+fn lerp(p1: vec2<f32>, p2: vec2<f32>, v1: f32, v2: f32) -> vec2<f32> {
+    let t = (1.0 - v1) / (v2 - v1);
+    return p1 + t * (p2 - p1);
+}
@@ -0,0 +1,80 @@
+use std::fmt::Write as _;
+
+use glam::*;
+
+use crate::{
+    mar_sq::{contour, cpu},
+    meta_shape::{MetaBall, MetaBox, MetaShapes},
+    theme::{Theme, ThemePropertyName},
+};
+
+const CELL_SIZE: u32 = 4;
+const SIMPLIFY_EPSILON: f32 = 0.5;
+
+/// Renders the liquid contours of the whole page as an SVG document.
+///
+/// Returns `None` when the page size cannot be read.
+pub fn export(meta_shapes: &MetaShapes) -> Option<String> {
+    let page_size = {
+        let doc_elem = web_sys::window()?.document()?.document_element()?;
+        IVec2::new(doc_elem.scroll_width(), doc_elem.scroll_height())
+            .max(IVec2::ZERO)
+            .as_uvec2()
+    };
+
+    Some(render_svg(
+        meta_shapes.balls(),
+        meta_shapes.boxes(),
+        page_size,
+    ))
+}
+
+pub fn render_svg(balls: &[MetaBall], boxes: &[MetaBox], page_size: UVec2) -> String {
+    let resolution = page_size / CELL_SIZE + UVec2::ONE;
+
+    let field = cpu::sample_field(
+        balls,
+        boxes,
+        resolution,
+        CELL_SIZE,
+        IVec2::ZERO,
+        IVec2::ZERO,
+    )
+    .into_iter()
+    .map(|cell| cell.x)
+    .collect::<Vec<_>>();
+
+    let path = contour::contours(&field, resolution, 1.0)
+        .into_iter()
+        .map(|contour| {
+            contour
+                .into_iter()
+                .map(|p| p * CELL_SIZE as f32)
+                .collect::<Vec<_>>()
+        })
+        .map(|contour| contour::simplify(&contour, SIMPLIFY_EPSILON))
+        .filter(|contour| contour.len() >= 3)
+        .fold(String::new(), |mut path, contour| {
+            for (i, p) in contour.iter().enumerate() {
+                let command = if i == 0 { 'M' } else { 'L' };
+                let _ = write!(path, "{command}{:.1} {:.1} ", p.x, p.y);
+            }
+            path.push_str("Z ");
+            path
+        });
+
+    let fill = Theme::current()
+        .properties()
+        .get(&ThemePropertyName::Foreground)
+        .map(|property| property.value())
+        .unwrap_or_else(|| "black".to_string());
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\
+         <path fill=\"{fill}\" fill-rule=\"evenodd\" d=\"{}\"/>\
+         </svg>",
+        path.trim_end(),
+        w = page_size.x,
+        h = page_size.y,
+    )
+}
@@ -1,21 +1,180 @@
 use glam::*;
-use wgpu::util::DeviceExt as _;
 
 use crate::frame::FrameMetadata;
 
+/// Anisotropic filtering for mipmapped sources scaled unevenly or rotated.
+const ANISOTROPY_CLAMP: u16 = 16;
+
+/// How the source texture fills the destination rectangle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum TextureFit {
+    /// Stretched to the rectangle, ignoring the aspect ratio.
+    #[default]
+    Stretch,
+    /// Scaled to fill the rectangle, cropping the overflow.
+    Cover,
+    /// Scaled to fit inside the rectangle, centered.
+    Contain,
+    /// Repeated at its own size times [`TextureBlit::tile_scale`].
+    Tile,
+}
+
+/// How the blitted texture is combined with the destination.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::EnumString,
+    strum::Display,
+    serde::Deserialize,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum TextureBlend {
+    #[default]
+    Alpha,
+    Additive,
+    Multiply,
+}
+
+impl TextureBlend {
+    const ALL: [Self; 3] = [Self::Alpha, Self::Additive, Self::Multiply];
+
+    /// Blend state for the premultiplied output of the blitter shader.
+    fn blend_state(self) -> wgpu::BlendState {
+        // Additive and multiply leave the destination alpha as is.
+        let keep_alpha = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::Zero,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+
+        match self {
+            Self::Alpha => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            Self::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            },
+            // dst * (1 - a + a * src)
+            Self::Multiply => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Dst,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            },
+        }
+    }
+}
+
+/// Where and how a texture is drawn by [`TextureBlitter::blit`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureBlit {
+    /// Top left of the destination rectangle in frame pixels.
+    pub top_left: Vec2,
+    /// Size of the destination rectangle in frame pixels.
+    pub size: Vec2,
+    pub fit: TextureFit,
+    /// Scale of the source texture when tiling.
+    pub tile_scale: f32,
+    /// Clockwise rotation around the rectangle center, in radians.
+    pub rotation: f32,
+    pub opacity: f32,
+    pub blend: TextureBlend,
+}
+
+impl TextureBlit {
+    pub fn new(top_left: Vec2, size: Vec2) -> Self {
+        Self {
+            top_left,
+            size,
+            fit: TextureFit::default(),
+            tile_scale: 1.0,
+            rotation: 0.0,
+            opacity: 1.0,
+            blend: TextureBlend::default(),
+        }
+    }
+
+    fn params(&self, src_size: Vec2) -> TextureBlitParams {
+        let src_size = src_size.max(Vec2::ONE);
+        let center = self.top_left + self.size * 0.5;
+
+        let (half_size, uv_scale) = match self.fit {
+            TextureFit::Stretch => (self.size * 0.5, Vec2::ONE),
+            TextureFit::Cover => {
+                let scale = (self.size / src_size).max_element();
+                (self.size * 0.5, self.size / (src_size * scale))
+            }
+            TextureFit::Contain => {
+                let scale = (self.size / src_size).min_element();
+                (src_size * scale * 0.5, Vec2::ONE)
+            }
+            TextureFit::Tile => (
+                self.size * 0.5,
+                self.size / (src_size * self.tile_scale.max(1e-3)),
+            ),
+        };
+
+        let uv_offset = match self.fit {
+            TextureFit::Cover => (Vec2::ONE - uv_scale) * 0.5,
+            _ => Vec2::ZERO,
+        };
+
+        TextureBlitParams {
+            center,
+            half_size,
+            uv_offset,
+            uv_scale,
+            rotation: self.rotation,
+            opacity: self.opacity.clamp(0.0, 1.0),
+            padding: Vec2::ZERO,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct TextureBlitParams {
-    position: Vec2,
-    scale: Vec2,
+    center: Vec2,
+    half_size: Vec2,
+    uv_offset: Vec2,
+    uv_scale: Vec2,
+    rotation: f32,
+    opacity: f32,
+    padding: Vec2,
 }
 
+/// The bind group of the prepared blit, rebuilt only when one of its resources changes.
+#[derive(Debug)]
+struct TextureBlitBindGroup {
+    /// Views of the same texture are assumed to be equivalent.
+    texture: wgpu::Texture,
+    tile: bool,
+    frame_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Draws one texture per submission, each caller owns its own blitter.
 #[derive(Debug)]
 pub struct TextureBlitter {
-    render_pipeline: wgpu::RenderPipeline,
+    /// One pipeline per [`TextureBlend`], in [`TextureBlend::ALL`] order.
+    render_pipelines: [wgpu::RenderPipeline; 3],
     bind_group_layout: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
+    tile_sampler: wgpu::Sampler,
     params_buffer: wgpu::Buffer,
+    bind_group: Option<TextureBlitBindGroup>,
+    blend: TextureBlend,
 }
 
 impl TextureBlitter {
@@ -46,7 +205,7 @@ impl TextureBlitter {
                 },
                 wgpu::BindGroupLayoutEntry {
                     binding: 2,
-                    visibility: wgpu::ShaderStages::VERTEX,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -74,83 +233,131 @@ impl TextureBlitter {
                 push_constant_ranges: &[],
             });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Texture Blitter Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader_module,
-                entry_point: Some("vert_main"),
-                buffers: &[],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader_module,
-                entry_point: Some("frag_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: texture_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
+        let render_pipelines = TextureBlend::ALL.map(|blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("Texture Blitter {blend} Render Pipeline")),
+                layout: Some(&render_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader_module,
+                    entry_point: Some("vert_main"),
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader_module,
+                    entry_point: Some("frag_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: texture_format,
+                        blend: Some(blend.blend_state()),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
         });
 
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("Texture Blitter Sampler"),
             mag_filter: wgpu::FilterMode::Linear,
             min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            anisotropy_clamp: ANISOTROPY_CLAMP,
+            ..Default::default()
+        });
+
+        let tile_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Texture Blitter Tile Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            anisotropy_clamp: ANISOTROPY_CLAMP,
             ..Default::default()
         });
 
-        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Texture Blitter Params Buffer"),
-            contents: bytemuck::bytes_of(&TextureBlitParams {
-                position: Vec2::ZERO,
-                scale: Vec2::ONE,
-            }),
+            size: std::mem::size_of::<TextureBlitParams>() as wgpu::BufferAddress,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
         Self {
-            render_pipeline,
+            render_pipelines,
             bind_group_layout,
             sampler,
+            tile_sampler,
             params_buffer,
+            bind_group: None,
+            blend: TextureBlend::default(),
         }
     }
 
-    pub fn copy(
-        &self,
+    /// Prepares and draws `src_view` over the whole of `dst_view`.
+    pub fn copy_full(
+        &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         encoder: &mut wgpu::CommandEncoder,
         src_view: &wgpu::TextureView,
         dst_view: &wgpu::TextureView,
         frame_metadata: &FrameMetadata,
-        position: IVec2,
-        scale: Vec2,
     ) {
-        queue.write_buffer(
-            &self.params_buffer,
-            0,
-            bytemuck::bytes_of(&TextureBlitParams {
-                position: position.as_vec2(),
-                scale,
-            }),
+        let src_size = src_view.texture().size();
+        let src_size = Vec2::new(src_size.width as f32, src_size.height as f32);
+
+        self.prepare_blit(
+            device,
+            queue,
+            src_view,
+            frame_metadata,
+            &TextureBlit::new(Vec2::ZERO, src_size),
         );
+        self.blit(encoder, dst_view);
+    }
+
+    /// Uploads the params of the next [`TextureBlitter::blit`], which must be in the same
+    /// submission.
+    pub fn prepare_blit(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        src_view: &wgpu::TextureView,
+        frame_metadata: &FrameMetadata,
+        blit: &TextureBlit,
+    ) {
+        let src_size = src_view.texture().size();
+        let params = blit.params(Vec2::new(src_size.width as f32, src_size.height as f32));
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+        self.blend = blit.blend;
+
+        let tile = blit.fit == TextureFit::Tile;
+        if self.bind_group.as_ref().is_some_and(|bind_group| {
+            bind_group.texture == *src_view.texture()
+                && bind_group.tile == tile
+                && bind_group.frame_buffer == *frame_metadata.buffer()
+        }) {
+            return;
+        }
+
+        let sampler = match tile {
+            true => &self.tile_sampler,
+            false => &self.sampler,
+        };
 
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Texture Blitter Bind Group"),
@@ -162,7 +369,7 @@ impl TextureBlitter {
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    resource: wgpu::BindingResource::Sampler(sampler),
                 },
                 wgpu::BindGroupEntry {
                     binding: 2,
@@ -175,6 +382,20 @@ impl TextureBlitter {
             ],
         });
 
+        self.bind_group = Some(TextureBlitBindGroup {
+            texture: src_view.texture().clone(),
+            tile,
+            frame_buffer: frame_metadata.buffer().clone(),
+            bind_group,
+        });
+    }
+
+    /// Draws the prepared blit over `dst_view`, does nothing if none was prepared.
+    pub fn blit(&self, encoder: &mut wgpu::CommandEncoder, dst_view: &wgpu::TextureView) {
+        let Some(bind_group) = &self.bind_group else {
+            return;
+        };
+
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Texture Blitter Render Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -191,29 +412,8 @@ impl TextureBlitter {
             timestamp_writes: None,
         });
 
-        render_pass.set_pipeline(&self.render_pipeline);
-        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_pipeline(&self.render_pipelines[self.blend as usize]);
+        render_pass.set_bind_group(0, &bind_group.bind_group, &[]);
         render_pass.draw(0..4, 0..1);
     }
-
-    pub fn copy_full(
-        &self,
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        encoder: &mut wgpu::CommandEncoder,
-        src_view: &wgpu::TextureView,
-        dst_view: &wgpu::TextureView,
-        frame_metadata: &FrameMetadata,
-    ) {
-        self.copy(
-            device,
-            queue,
-            encoder,
-            src_view,
-            dst_view,
-            frame_metadata,
-            IVec2::ZERO,
-            Vec2::ONE,
-        );
-    }
 }
@@ -46,10 +46,110 @@ impl Theme {
                 .expect_throw("set property");
         }
     }
+
+    /// Resolves the theme colours of an SVG, since it is rasterised outside of the document.
+    ///
+    /// `var(--<property>)` is replaced by the property, ignoring any fallback, and elements with
+    /// the `theme-fill-<property>` or `theme-stroke-<property>` class are painted with it.
+    pub fn recolor_svg(&self, svg: &str) -> String {
+        let mut svg = replace_vars(svg, |name| {
+            let name = name.parse::<ThemePropertyName>().ok()?;
+            Some(self.properties().get(&name)?.value())
+        });
+
+        let mut style = String::from("<style>");
+        for (name, property) in self.properties() {
+            let value = property.value();
+            style.push_str(&format!(
+                ".theme-fill-{name} {{ fill: {value}; }} .theme-stroke-{name} {{ stroke: {value}; }}"
+            ));
+        }
+        style.push_str("</style>");
+
+        if let Some(end) = svg_open_tag_end(&svg) {
+            svg.insert_str(end, &style);
+        }
+
+        svg
+    }
+}
+
+/// Replaces every `var(--<name>)` or `var(--<name>, <fallback>)` that `value` knows the name of.
+fn replace_vars(svg: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    const VAR: &str = "var(--";
+
+    let mut result = String::with_capacity(svg.len());
+    let mut rest = svg;
+    while let Some(start) = rest.find(VAR) {
+        result.push_str(&rest[..start]);
+        let args = &rest[start + VAR.len()..];
+
+        // The fallback may have parentheses of its own.
+        let mut depth = 0;
+        let end = args.char_indices().find_map(|(i, c)| match c {
+            '(' => {
+                depth += 1;
+                None
+            }
+            ')' if depth == 0 => Some(i),
+            ')' => {
+                depth -= 1;
+                None
+            }
+            _ => None,
+        });
+
+        let replaced = end.and_then(|end| {
+            let name = args[..end].split(',').next().unwrap_or_default().trim();
+            Some((value(name)?, end))
+        });
+
+        match replaced {
+            Some((value, end)) => {
+                result.push_str(&value);
+                rest = &args[end + 1..];
+            }
+            None => {
+                result.push_str(VAR);
+                rest = args;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Byte index right after the `>` of the opening `<svg>` tag, skipping quoted attribute values.
+///
+/// `None` if there is no such tag or it is self-closing.
+fn svg_open_tag_end(svg: &str) -> Option<usize> {
+    let start = svg.match_indices("<svg").map(|(i, _)| i).find(|&i| {
+        svg[i + "<svg".len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_whitespace() || c == '>' || c == '/')
+    })?;
+
+    let mut quote = None;
+    let mut prev = None;
+    for (i, c) in svg[start..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') if prev == Some('/') => return None,
+            (None, '>') => return Some(start + i + 1),
+            _ => {}
+        }
+        prev = Some(c);
+    }
+
+    None
 }
 
 #[derive(Debug, strum::EnumDiscriminants)]
-#[strum_discriminants(derive(Hash, strum::Display))]
+#[strum_discriminants(derive(Hash, strum::Display, strum::EnumString))]
 #[strum_discriminants(strum(serialize_all = "kebab-case"))]
 #[strum_discriminants(name(ThemePropertyName))]
 pub enum ThemeProperty {
@@ -118,3 +218,65 @@ fn dark() -> &'static HashMap<ThemePropertyName, ThemeProperty> {
 
     &THEME
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(name: ThemePropertyName) -> String {
+        Theme::Dark.properties()[&name].value()
+    }
+
+    #[test]
+    fn vars_are_replaced() {
+        let svg = Theme::Dark.recolor_svg(
+            r#"<svg><path style="fill:var(--foreground);stroke:var(--hk , rgb(1,2,3))"/></svg>"#,
+        );
+
+        assert!(svg.contains(&format!(
+            r#"style="fill:{};stroke:{}""#,
+            value(ThemePropertyName::Foreground),
+            value(ThemePropertyName::Hk),
+        )));
+        assert!(!svg.contains("var("));
+    }
+
+    #[test]
+    fn unknown_vars_are_kept() {
+        let svg = Theme::Dark.recolor_svg(r#"<svg><path fill="var(--unknown, white)"/></svg>"#);
+
+        assert!(svg.contains(r#"fill="var(--unknown, white)""#));
+    }
+
+    #[test]
+    fn classes_are_styled() {
+        let svg = Theme::Dark.recolor_svg("<svg></svg>");
+
+        assert!(svg.contains(&format!(
+            ".theme-fill-background {{ fill: {}; }}",
+            value(ThemePropertyName::Background),
+        )));
+        assert!(svg.contains(&format!(
+            ".theme-stroke-foreground {{ stroke: {}; }}",
+            value(ThemePropertyName::Foreground),
+        )));
+    }
+
+    #[test]
+    fn style_is_inserted_after_the_svg_open_tag() {
+        let svg = Theme::Dark.recolor_svg(concat!(
+            r#"<?xml version="1.0"?><!DOCTYPE svg><svgz/>"#,
+            r#"<svg data-a="1>2" data-b='>' style="fill:red;"><g/></svg>"#,
+        ));
+
+        let open_tag = r#"<svg data-a="1>2" data-b='>' style="fill:red;">"#;
+        let start = svg.find(open_tag).expect("open tag kept") + open_tag.len();
+        assert!(svg[start..].starts_with("<style>"));
+        assert_eq!(svg.matches("<style>").count(), 1);
+    }
+
+    #[test]
+    fn self_closing_svg_is_left_alone() {
+        assert_eq!(Theme::Dark.recolor_svg("<svg/>"), "<svg/>");
+    }
+}